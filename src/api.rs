@@ -0,0 +1,188 @@
+use js_sys::Array;
+use wasm_bindgen::prelude::*;
+use ndarray::Array2;
+use crate::coordinate::Coordinate;
+use crate::datetime::{DateOrder, DateParsing};
+use crate::value::Value;
+use crate::{DataFrame, FUNCTION};
+
+//JavaScript facing API. Every method reports failures as a thrown Error rather than a panic.
+
+#[wasm_bindgen(start)]
+pub fn init() {
+    //Forward Rust panics to the browser console instead of an opaque "unreachable" error
+    console_error_panic_hook::set_once();
+}
+
+#[wasm_bindgen]
+impl DataFrame {
+    #[wasm_bindgen(constructor)]
+    pub fn from_js(data: Array) -> Result<DataFrame, JsError> {
+        /*
+        Creates a DataFrame from a 2-D array of cell inputs. Strings are parsed the same as typed input, numbers and
+        booleans are used as their text, null/undefined are empty cells and shorter rows are padded with empty cells.
+         */
+        let mut rows: Vec<Vec<String>> = vec![];
+        for (i, row) in data.iter().enumerate() {
+            if !Array::is_array(&row) {
+                return Err(JsError::new(&format!("Row {} is not an array", i)))
+            }
+            rows.push(Array::from(&row).iter().map(|cell| js_to_input(&cell)).collect());
+        }
+
+        let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        for row in rows.iter_mut() {
+            row.resize(columns, "".to_string());
+        }
+        Ok(DataFrame::new(rows))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn js_from_json(text: &str) -> Result<DataFrame, JsError> {
+        //Loads a DataFrame saved with toJson
+        DataFrame::from_json(text).map_err(|error| JsError::new(&error.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn js_to_json(&self) -> String {
+        self.to_json()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rows(&self) -> usize {
+        self.dim().0
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn columns(&self) -> usize {
+        self.dim().1
+    }
+
+    #[wasm_bindgen(js_name = setCell)]
+    pub fn js_set_cell(&mut self, row: usize, column: usize, text: &str) -> Result<(), JsError> {
+        self.set_cell_from_str(Coordinate{row, column}, text)
+            .map(|_| ())
+            .map_err(|error| JsError::new(&error))
+    }
+
+    #[wasm_bindgen(js_name = setMonetary)]
+    pub fn js_set_monetary(&mut self, column: usize, places: Option<usize>) -> Result<(), JsError> {
+        //Pass undefined as places to clear the declaration
+        self.set_monetary(column, places)
+            .map(|_| ())
+            .map_err(|error| JsError::new(&error))
+    }
+
+    #[wasm_bindgen(js_name = setDayFirst)]
+    pub fn js_set_day_first(&mut self, day_first: bool) {
+        //Whether an ambiguous date such as 01/02/2021 typed from now on is the 1st of February or the 2nd of January
+        let order = if day_first {DateOrder::DayFirst} else {DateOrder::MonthFirst};
+        self.set_date_parsing(DateParsing{order, ..self.date_parsing().clone()});
+    }
+
+    #[wasm_bindgen(js_name = insertRows)]
+    pub fn js_insert_rows(&mut self, at: usize, count: usize) -> Result<(), JsError> {
+        self.insert_rows(at, count).map_err(|error| JsError::new(&error))
+    }
+
+    #[wasm_bindgen(js_name = deleteRows)]
+    pub fn js_delete_rows(&mut self, at: usize, count: usize) -> Result<(), JsError> {
+        self.delete_rows(at, count).map_err(|error| JsError::new(&error))
+    }
+
+    #[wasm_bindgen(js_name = insertColumns)]
+    pub fn js_insert_columns(&mut self, at: usize, count: usize) -> Result<(), JsError> {
+        self.insert_columns(at, count).map_err(|error| JsError::new(&error))
+    }
+
+    #[wasm_bindgen(js_name = deleteColumns)]
+    pub fn js_delete_columns(&mut self, at: usize, count: usize) -> Result<(), JsError> {
+        self.delete_columns(at, count).map_err(|error| JsError::new(&error))
+    }
+
+    #[wasm_bindgen(js_name = fill)]
+    pub fn js_fill(&mut self, row: usize, column: usize, start_row: usize, start_column: usize, end_row: usize, end_column: usize) -> Result<(), JsError> {
+        //Copies a cell into the cells between two corners, relative references move with each copy
+        self.fill(Coordinate{row, column}, Coordinate{row: start_row, column: start_column}, Coordinate{row: end_row, column: end_column})
+            .map(|_| ())
+            .map_err(|error| JsError::new(&error))
+    }
+
+    #[wasm_bindgen(js_name = apply)]
+    pub fn js_apply(&mut self, start_row: usize, start_column: usize, end_row: usize, end_column: usize, function: &str, operand: JsValue)
+        -> Result<Array, JsError> {
+        /*
+        Applies a function such as "ADD" to the cells between two corners and a single value or a 2-D array of them.
+        Returns the cells the function failed on as the cell followed by its error, e.g. "A0 #VALUE!"
+         */
+        let function = FUNCTION::from_name(function).ok_or_else(|| JsError::new(&format!("Unknown function {}", function)))?;
+        let operand = js_to_operand(&operand)?;
+        let failed = self.apply(
+            Coordinate{row: start_row, column: start_column},
+            Coordinate{row: end_row, column: end_column},
+            function,
+            &operand
+        ).map_err(|error| JsError::new(&error))?;
+
+        Ok(failed.into_iter().map(|(coordinate, kind)| JsValue::from(format!("{} {}", coordinate, kind))).collect())
+    }
+
+    #[wasm_bindgen(js_name = getDisplay)]
+    pub fn js_get_display(&self, row: usize, column: usize) -> Result<String, JsError> {
+        //The solved value as it should be shown in the cell
+        self.display(Coordinate{row, column})
+            .ok_or_else(|| out_of_bounds(row, column))
+    }
+
+    #[wasm_bindgen(js_name = getRaw)]
+    pub fn js_get_raw(&self, row: usize, column: usize) -> Result<String, JsError> {
+        //The cell as entered, formulas are not solved
+        self.input(Coordinate{row, column})
+            .ok_or_else(|| out_of_bounds(row, column))
+    }
+
+    #[wasm_bindgen(js_name = evaluateRange)]
+    pub fn js_evaluate_range(&self, start_row: usize, start_column: usize, end_row: usize, end_column: usize) -> Result<Array, JsError> {
+        //The solved values between two corners as a 2-D array of display strings
+        let values = self.evaluate(Coordinate{row: start_row, column: start_column}, Coordinate{row: end_row, column: end_column})
+            .map_err(|error| JsError::new(&error))?;
+
+        Ok(values.rows().into_iter()
+            .map(|row| row.iter().map(|value| JsValue::from(value.display())).collect::<Array>())
+            .collect())
+    }
+}
+
+fn js_to_input(cell: &JsValue) -> String {
+    if let Some(text) = cell.as_string() {
+        return text
+    }
+    if let Some(number) = cell.as_f64() {
+        return number.to_string()
+    }
+    if let Some(boolean) = cell.as_bool() {
+        return boolean.to_string().to_uppercase()
+    }
+    "".to_string()
+}
+
+fn js_to_operand(operand: &JsValue) -> Result<Array2<Value>, JsError> {
+    //A 2-D array of cell inputs, anything else is a single value
+    if !Array::is_array(operand) {
+        return Ok(Array2::from_elem((1, 1), Value::create_from_str(js_to_input(operand))))
+    }
+    let mut rows: Vec<Vec<Value>> = vec![];
+    for (i, row) in Array::from(operand).iter().enumerate() {
+        if !Array::is_array(&row) {
+            return Err(JsError::new(&format!("Row {} of the operand is not an array", i)))
+        }
+        rows.push(Array::from(&row).iter().map(|cell| Value::create_from_str(js_to_input(&cell))).collect());
+    }
+    let columns = rows.first().map_or(0, |row| row.len());
+    Array2::from_shape_vec((rows.len(), columns), rows.concat())
+        .map_err(|_| JsError::new("Every row of the operand has to be the same length"))
+}
+
+fn out_of_bounds(row: usize, column: usize) -> JsError {
+    JsError::new(&format!("Cell {} is outside of the DataFrame", Coordinate{row, column}))
+}
@@ -0,0 +1,158 @@
+use ndarray::Array2;
+use crate::coordinate::Coordinate;
+use crate::error::ErrorKind;
+use crate::range::Range;
+use crate::value::{solve_function, Value};
+use crate::{DataFrame, FUNCTION};
+
+impl DataFrame {
+    pub fn apply(&mut self, corner_1: Coordinate, corner_2: Coordinate, function: FUNCTION, operand: &Array2<Value>)
+        -> Result<Vec<(Coordinate, ErrorKind)>, String> {
+        /*
+        Replaces every cell between two opposite corners (inclusive) with function applied to the cell and the matching
+        value of operand. The operand is broadcast over the cells, it can be a single value, a row as wide as the cells,
+        a column as tall as them or a block the same size. Formulas are kept by applying the function on top of them,
+        anything else is replaced by the result. Cells the function fails on are left as they were and returned with
+        the error, every other cell is changed and solved again.
+         */
+        self.check_bounds(corner_1)?;
+        self.check_bounds(corner_2)?;
+        let (start, end) = match Range::cells(corner_1, corner_2) {
+            Range::Cells(start, end) => (start, end),
+            _ => unreachable!("Range::cells always creates a range of cells")
+        };
+        let (rows, columns) = (end.row - start.row + 1, end.column - start.column + 1);
+        let (operand_rows, operand_columns) = operand.dim();
+        if !(operand_rows == 1 || operand_rows == rows) || !(operand_columns == 1 || operand_columns == columns) {
+            return Err(format!(
+                "Can not apply a {}x{} operand to {} rows and {} columns, it has to be a single value, a row, a column or the same size",
+                operand_rows, operand_columns, rows, columns
+            ))
+        }
+
+        let mut changed = vec![];
+        let mut failed = vec![];
+        for row in 0..rows {
+            for column in 0..columns {
+                let coordinate = Coordinate{row: start.row + row, column: start.column + column};
+                let arguments = vec![
+                    self.data[(coordinate.row, coordinate.column)].clone(),
+                    operand[(row % operand_rows, column % operand_columns)].clone()
+                ];
+                let result = if arguments.iter().any(is_formula) {
+                    Ok(Value::FUNCTION(function.clone(), arguments))
+                } else {
+                    solve_function(&function, &arguments)
+                };
+                match result {
+                    Ok(value) => {
+                        self.data[(coordinate.row, coordinate.column)] = value;
+                        self.unlink(coordinate);
+                        self.link(coordinate);
+                        changed.push(coordinate);
+                    },
+                    Err(kind) => failed.push((coordinate, kind))
+                }
+            }
+        }
+        self.recalculate(&changed);
+        Ok(failed)
+    }
+}
+
+fn is_formula(value: &Value) -> bool {
+    matches!(value, Value::FUNCTION(_, _) | Value::CELL_REFERENCE(_, _) | Value::RANGE(_, _) | Value::SHEET_REFERENCE(_, _))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::prelude::*;
+    use crate::error::ErrorKind;
+    use crate::test_helpers::coordinate;
+    use crate::value::Value;
+    use crate::{DataFrame, FUNCTION};
+
+    #[test]
+    fn test_df_apply_add(){
+        //Testing apply on all values in column for a simple addition of 2
+        let mut input = DataFrame::from_values(arr2(&[
+            [Value::I32(1), Value::I32(4)],
+            [Value::I32(3), Value::I32(5)],
+            [Value::I32(2), Value::I32(6)]
+        ]));
+
+        let expected = arr2(&[
+            [Value::I32(3), Value::I32(4)],
+            [Value::I32(5), Value::I32(5)],
+            [Value::I32(4), Value::I32(6)]
+        ]);
+
+        //Apply the addition of 2 to all values in column
+        let failed = input.apply(coordinate(0, 0), coordinate(2, 0), FUNCTION::ADD, &arr2(&[
+            [Value::I32(2)],
+            [Value::I32(2)],
+            [Value::I32(2)]
+        ])).unwrap();
+
+        assert!(failed.is_empty());
+        assert_eq!(expected, input.data);
+    }
+
+    #[test]
+    fn test_apply_broadcast(){
+        let values = || DataFrame::from_values(arr2(&[[Value::I32(1), Value::I32(2)], [Value::I32(3), Value::I32(4)]]));
+
+        let mut input = values();
+        input.apply(coordinate(0, 0), coordinate(1, 1), FUNCTION::MUL, &arr2(&[[Value::I32(10)]])).unwrap();
+        assert_eq!(arr2(&[[Value::I32(10), Value::I32(20)], [Value::I32(30), Value::I32(40)]]), input.data);
+
+        let mut input = values();
+        input.apply(coordinate(1, 1), coordinate(0, 0), FUNCTION::ADD, &arr2(&[[Value::I32(10), Value::I32(20)]])).unwrap();
+        assert_eq!(arr2(&[[Value::I32(11), Value::I32(22)], [Value::I32(13), Value::I32(24)]]), input.data);
+
+        let mut input = values();
+        input.apply(coordinate(0, 0), coordinate(1, 1), FUNCTION::SUB, &arr2(&[[Value::I32(1)], [Value::I32(2)]])).unwrap();
+        assert_eq!(arr2(&[[Value::I32(0), Value::I32(1)], [Value::I32(1), Value::I32(2)]]), input.data);
+
+        let mut input = values();
+        let block = arr2(&[[Value::STRING("a".to_owned()), Value::STRING("b".to_owned())]]);
+        input.apply(coordinate(1, 0), coordinate(1, 1), FUNCTION::CONCAT, &block).unwrap();
+        assert_eq!(Value::STRING("3a".to_owned()), input.data[(1, 0)]);
+        assert_eq!(Value::STRING("4b".to_owned()), input.data[(1, 1)]);
+
+        let operand = arr2(&[[Value::I32(1), Value::I32(2), Value::I32(3)]]);
+        assert!(values().apply(coordinate(0, 0), coordinate(1, 1), FUNCTION::ADD, &operand).is_err());
+        assert!(values().apply(coordinate(0, 0), coordinate(2, 0), FUNCTION::ADD, &arr2(&[[Value::I32(1)]])).is_err());
+    }
+
+    #[test]
+    fn test_apply_formulas_and_errors(){
+        let mut input = DataFrame::new(vec![
+            vec!["1".to_string(), "=[A0]*2".to_string()],
+            vec!["text".to_string(), "4".to_string()]
+        ]);
+        let failed = input.apply(coordinate(0, 0), coordinate(1, 1), FUNCTION::ADD, &arr2(&[[Value::I32(1)]])).unwrap();
+
+        //Formulas are kept and solved with the new values they reference
+        assert_eq!("=[A0]*2+1", input.raw(coordinate(0, 1)).unwrap().to_string());
+        assert_eq!(Some(&Value::I32(5)), input.value(coordinate(0, 1)));
+        assert_eq!(Some(&Value::I32(5)), input.value(coordinate(1, 1)));
+
+        //Text can not be added to so it is reported and left alone
+        assert_eq!(vec![(coordinate(1, 0), ErrorKind::VALUE)], failed);
+        assert_eq!(Some(&Value::STRING("text".to_owned())), input.value(coordinate(1, 0)));
+    }
+
+    #[test]
+    fn test_from_values(){
+        //Cells given as values are used as they are and solved straight away
+        let input = DataFrame::from_values(arr2(&[
+            [Value::I32(1), Value::STRING("text".to_string())],
+            [Value::F32(2.5, 2), Value::NULL()]
+        ]));
+        assert_eq!((2, 2), input.dim());
+        assert_eq!(Some(&Value::STRING("text".to_owned())), input.value(coordinate(0, 1)));
+        assert_eq!(Some("2.50".to_owned()), input.display(coordinate(1, 0)));
+        assert_eq!(Some(&Value::NULL()), input.value(coordinate(1, 1)));
+    }
+}
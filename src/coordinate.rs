@@ -0,0 +1,93 @@
+use std::fmt;
+
+#[derive(PartialEq)]
+#[derive(Eq)]
+#[derive(PartialOrd)]
+#[derive(Ord)]
+#[derive(Hash)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct Coordinate{
+    pub row: usize,
+    pub column: usize
+}
+
+impl Coordinate{
+    pub fn column_name(column: usize) -> String {
+        /*
+        Converts a 0 indexed column number into its spreadsheet letters, e.g. 0 -> A, 26 -> AA
+         */
+        let mut name = String::new();
+        let mut remaining = column + 1;
+        while remaining > 0 {
+            let rem = (remaining - 1) % 26;
+            name.insert(0, (b'A' + rem as u8) as char);
+            remaining = (remaining - 1) / 26;
+        }
+        name
+    }
+
+    pub fn column_number(name: &str) -> Option<usize> {
+        /*
+        Converts spreadsheet column letters into a 0 indexed column number, e.g. A -> 0, AA -> 26
+         */
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None
+        }
+
+        name.to_uppercase().chars()
+            .try_fold(0usize, |acc, c| acc.checked_mul(26)?.checked_add(c as usize - 64))
+            .map(|v| v - 1) //Minus 1 as 0 indexing
+    }
+}
+
+//Which parts of a cell reference are fixed with a $ when its formula is filled or copied, e.g. $A1 fixes the column
+#[derive(PartialEq)]
+#[derive(Eq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Default)]
+pub struct Anchor{
+    pub row: bool,
+    pub column: bool
+}
+
+impl Anchor{
+    pub const RELATIVE: Anchor = Anchor{row: false, column: false};
+    pub const ABSOLUTE: Anchor = Anchor{row: true, column: true};
+}
+
+//Formatting for Coordinate, matches the syntax used inside a cell reference
+impl fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", Coordinate::column_name(self.column), self.row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coordinate::Coordinate;
+
+    #[test]
+    fn test_column_name(){
+        assert_eq!("A", Coordinate::column_name(0));
+        assert_eq!("Z", Coordinate::column_name(25));
+        assert_eq!("AA", Coordinate::column_name(26));
+        assert_eq!("ZA", Coordinate::column_name(676));
+    }
+
+    #[test]
+    fn test_column_number(){
+        assert_eq!(Some(0), Coordinate::column_number("A"));
+        assert_eq!(Some(26), Coordinate::column_number("aa"));
+        assert_eq!(Some(676), Coordinate::column_number("ZA"));
+        assert_eq!(None, Coordinate::column_number("A1"));
+    }
+
+    #[test]
+    fn test_display(){
+        assert_eq!("ZA62", Coordinate{row:62, column:676}.to_string())
+    }
+}
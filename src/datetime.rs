@@ -0,0 +1,212 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use crate::value::Value;
+use crate::DataFrame;
+
+//Display formats of the values each date format creates, they are read back by the same format
+const COMPACT_FORMAT: &str = "%Y%m%d %H%M%S";
+const ISO_TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f%:z";
+const ISO_DATETIME_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+const ISO_DATE_FORMAT: &str = "%Y-%m-%d";
+const ISO_TIME_FORMATS: [&str; 2] = ["%H:%M:%S%.f", "%H:%M"];
+const RFC_2822_FORMAT: &str = "%a, %d %b %Y %H:%M:%S %z";
+
+//A family of date and time text read from input
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Serialize)]
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DateFormat{
+    //20210418 210328
+    Compact,
+    //2021-04-18, 21:03:28, 2021-04-18T21:03:28 and with an offset 2021-04-18T21:03:28+10:00 or Z
+    Iso8601,
+    //18/04/2021, optionally followed by a time such as 18/04/2021 21:03
+    DayMonthYear,
+    //04/18/2021, optionally followed by a time
+    MonthDayYear,
+    //Sun, 18 Apr 2021 21:03:28 +1000
+    Rfc2822
+}
+
+//Which of day/month/year and month/day/year an ambiguous date such as 01/02/2021 is read as
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Serialize)]
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DateOrder{
+    DayFirst,
+    MonthFirst
+}
+
+//The date formats input is read with, text matching none of them is not a date
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Serialize)]
+#[derive(Deserialize)]
+pub struct DateParsing{
+    pub formats: Vec<DateFormat>,
+    pub order: DateOrder
+}
+
+impl Default for DateParsing {
+    //Every format, with ambiguous dates read day first
+    fn default() -> Self {
+        DateParsing{
+            formats: vec![DateFormat::Compact, DateFormat::Iso8601, DateFormat::DayMonthYear, DateFormat::MonthDayYear, DateFormat::Rfc2822],
+            order: DateOrder::DayFirst
+        }
+    }
+}
+
+impl DateParsing {
+    pub(crate) fn parse(&self, text: &str) -> Option<Value> {
+        /*
+        Reads text as a DATE, TIME, DATETIME or TIMESTAMP with the first format it matches. Day/month/year and
+        month/day/year can both match a date such as 01/02/2021, the one the order prefers is tried first.
+         */
+        let mut formats = self.formats.clone();
+        formats.sort_by_key(|format| matches!(
+            (format, self.order),
+            (DateFormat::MonthDayYear, DateOrder::DayFirst) | (DateFormat::DayMonthYear, DateOrder::MonthFirst)
+        ));
+        formats.iter().find_map(|format| format.parse(text.trim()))
+    }
+}
+
+impl DateFormat {
+    fn parse(&self, text: &str) -> Option<Value> {
+        match self {
+            DateFormat::Compact => NaiveDateTime::parse_from_str(text, COMPACT_FORMAT).ok()
+                .map(|datetime| Value::DATETIME(datetime, COMPACT_FORMAT.to_owned())),
+            DateFormat::Iso8601 => DateTime::parse_from_rfc3339(text).ok()
+                .map(|timestamp| Value::TIMESTAMP(timestamp, ISO_TIMESTAMP_FORMAT.to_owned()))
+                .or_else(|| ISO_DATETIME_FORMATS.iter().find_map(|format|
+                    NaiveDateTime::parse_from_str(text, format).ok().map(|datetime| Value::DATETIME(datetime, format.to_string()))
+                ))
+                .or_else(|| NaiveDate::parse_from_str(text, ISO_DATE_FORMAT).ok().map(|date| Value::DATE(date, ISO_DATE_FORMAT.to_owned())))
+                .or_else(|| ISO_TIME_FORMATS.iter().find_map(|format|
+                    NaiveTime::parse_from_str(text, format).ok().map(|time| Value::TIME(time, format.to_string()))
+                )),
+            DateFormat::DayMonthYear => slashed(text, "%d/%m/%Y"),
+            DateFormat::MonthDayYear => slashed(text, "%m/%d/%Y"),
+            DateFormat::Rfc2822 => DateTime::parse_from_rfc2822(text).ok()
+                .map(|timestamp| Value::TIMESTAMP(timestamp, RFC_2822_FORMAT.to_owned()))
+        }
+    }
+}
+
+fn slashed(text: &str, date_format: &str) -> Option<Value> {
+    //A date with a four digit year, optionally followed by a time with or without seconds
+    let (date, time) = text.split_once(' ').unwrap_or((text, ""));
+    if date.rsplit('/').next().is_none_or(|year| year.len() != 4) {
+        return None
+    }
+    if time.is_empty() {
+        return NaiveDate::parse_from_str(date, date_format).ok().map(|date| Value::DATE(date, date_format.to_owned()))
+    }
+    ["%H:%M:%S", "%H:%M"].iter().find_map(|time_format| {
+        let format = format!("{} {}", date_format, time_format);
+        NaiveDateTime::parse_from_str(text, &format).ok().map(|datetime| Value::DATETIME(datetime, format))
+    })
+}
+
+impl DataFrame {
+    pub fn set_date_parsing(&mut self, dates: DateParsing) {
+        /*
+        Sets the date formats input typed into the DataFrame is read with. Cells already entered keep the values
+        they were read as.
+         */
+        self.dates = dates;
+    }
+
+    pub fn date_parsing(&self) -> &DateParsing {
+        &self.dates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+    use crate::coordinate::Coordinate;
+    use crate::datetime::{DateFormat, DateOrder, DateParsing};
+    use crate::value::Value;
+    use crate::DataFrame;
+
+    fn date(text: &str, format: &str) -> Value {
+        Value::DATE(NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap(), format.to_owned())
+    }
+
+    #[test]
+    fn test_formats() {
+        let dates = DateParsing::default();
+        assert_eq!(Some(date("2021-04-18", "%Y-%m-%d")), dates.parse("2021-04-18"));
+        assert_eq!(Some(date("2021-04-18", "%d/%m/%Y")), dates.parse("18/04/2021"));
+        assert_eq!(Some(date("2021-04-18", "%m/%d/%Y")), dates.parse("04/18/2021"));
+        assert_eq!(
+            Some(Value::TIME(NaiveTime::from_hms_opt(21, 3, 0).unwrap(), "%H:%M".to_owned())),
+            dates.parse("21:03")
+        );
+        assert_eq!(
+            Some(Value::DATETIME(NaiveDateTime::parse_from_str("20210418 210328", "%Y%m%d %H%M%S").unwrap(), "%Y-%m-%dT%H:%M:%S%.f".to_owned())),
+            dates.parse("2021-04-18T21:03:28")
+        );
+        assert_eq!(
+            Some(Value::DATETIME(NaiveDateTime::parse_from_str("20210418 210300", "%Y%m%d %H%M%S").unwrap(), "%d/%m/%Y %H:%M".to_owned())),
+            dates.parse("18/04/2021 21:03")
+        );
+        assert_eq!(None, dates.parse("18/04/21"));
+        assert_eq!(None, dates.parse("2021-02-30"));
+    }
+
+    #[test]
+    fn test_timestamps() {
+        let dates = DateParsing::default();
+        let expected = DateTime::parse_from_rfc3339("2021-04-18T21:03:28+10:00").unwrap();
+        assert_eq!(
+            Some(Value::TIMESTAMP(expected, "%Y-%m-%dT%H:%M:%S%.f%:z".to_owned())),
+            dates.parse("2021-04-18T11:03:28Z")
+        );
+        assert_eq!(
+            Some(Value::TIMESTAMP(expected, "%a, %d %b %Y %H:%M:%S %z".to_owned())),
+            dates.parse("Sun, 18 Apr 2021 21:03:28 +1000")
+        );
+        //The offset is kept so the time is shown as it was typed
+        assert_eq!("2021-04-18T21:03:28.500+10:00", dates.parse("2021-04-18T21:03:28.5+10:00").unwrap().to_string());
+    }
+
+    #[test]
+    fn test_order() {
+        let mut dates = DateParsing::default();
+        assert_eq!(Some(date("2021-02-01", "%d/%m/%Y")), dates.parse("01/02/2021"));
+        dates.order = DateOrder::MonthFirst;
+        assert_eq!(Some(date("2021-01-02", "%m/%d/%Y")), dates.parse("01/02/2021"));
+        //Only one order can read a day after the 12th
+        assert_eq!(Some(date("2021-04-18", "%d/%m/%Y")), dates.parse("18/04/2021"));
+
+        dates.formats = vec![DateFormat::DayMonthYear];
+        assert_eq!(Some(date("2021-02-01", "%d/%m/%Y")), dates.parse("01/02/2021"));
+        assert_eq!(None, dates.parse("2021-04-18"));
+    }
+
+    #[test]
+    fn test_date_parsing(){
+        let mut input = DataFrame::new(vec![vec!["01/02/2021".to_string(), "".to_string()]]);
+        assert_eq!(Some(&date("2021-02-01", "%d/%m/%Y")), input.value(Coordinate{row: 0, column: 0}));
+
+        input.set_date_parsing(DateParsing{formats: vec![DateFormat::MonthDayYear], order: DateOrder::MonthFirst});
+        input.set_cell_from_str(Coordinate{row: 0, column: 1}, "01/02/2021").unwrap();
+        assert_eq!(Some(&date("2021-01-02", "%m/%d/%Y")), input.value(Coordinate{row: 0, column: 1}));
+        input.set_cell_from_str(Coordinate{row: 0, column: 1}, "2021-04-18").unwrap();
+        assert_eq!(Some(&Value::STRING("2021-04-18".to_owned())), input.value(Coordinate{row: 0, column: 1}));
+        //Cells entered before the change keep their value
+        assert_eq!(Some(&date("2021-02-01", "%d/%m/%Y")), input.value(Coordinate{row: 0, column: 0}));
+    }
+}
@@ -0,0 +1,441 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+use crate::coordinate::Coordinate;
+use crate::error::ErrorKind;
+use crate::iteration::{iterate, Convergence};
+use crate::value::Value;
+use crate::DataFrame;
+
+//Cells linked by the formulas referencing each other, either the cells of one DataFrame or of every sheet of a Workbook
+pub(crate) trait Graph {
+    type Cell: Copy + Eq + Hash + Ord;
+
+    //The cells whose formulas reference the given cell
+    fn dependents(&self, cell: &Self::Cell) -> Vec<Self::Cell>;
+    //The cells the formula of the given cell references
+    fn precedents(&self, cell: &Self::Cell) -> Vec<Self::Cell>;
+    //Solves a cell from the solved values of the cells it references
+    fn solve(&self, cell: &Self::Cell) -> Value;
+    fn solved_mut(&mut self, cell: &Self::Cell) -> &mut Value;
+    //Drops any loops found before through the given cells, they are found again if they still exist
+    fn forget_loops(&mut self, cells: &HashSet<Self::Cell>);
+    //Solves a loop of cells with nothing left to wait on outside of itself, path is the loop in reference order
+    fn solve_loop(&mut self, component: &[Self::Cell], path: Vec<Self::Cell>);
+}
+
+impl DataFrame {
+    pub fn set_cell(&mut self, coordinate: Coordinate, value: Value) -> Result<Vec<Coordinate>, ErrorKind> {
+        /*
+        Replaces the value in a cell and recalculates it along with every cell depending on it.
+        Returns the recalculated cells in the order they were solved.
+         */
+        let cell = self.data.get_mut((coordinate.row, coordinate.column)).ok_or(ErrorKind::REF)?;
+        *cell = value;
+
+        self.unlink(coordinate);
+        self.link(coordinate);
+        Ok(self.recalculate(&[coordinate]))
+    }
+
+    pub(crate) fn link(&mut self, coordinate: Coordinate) {
+        //Records the cells the given cell references and adds it as a dependent of each of them
+        let mut precedents = self.data[(coordinate.row, coordinate.column)].precedents(self.data.dim());
+        precedents.sort();
+        precedents.dedup();
+
+        for precedent in &precedents {
+            self.references.entry(*precedent).or_default().push(coordinate);
+        }
+        if !precedents.is_empty() {
+            self.precedents.insert(coordinate, precedents);
+        }
+    }
+
+    pub(crate) fn unlink(&mut self, coordinate: Coordinate) {
+        //Removes the given cell as a dependent of every cell it referenced
+        for precedent in self.precedents.remove(&coordinate).unwrap_or_default() {
+            if let Some(dependents) = self.references.get_mut(&precedent) {
+                dependents.retain(|dependent| *dependent != coordinate);
+                if dependents.is_empty() {
+                    self.references.remove(&precedent);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn recalculate(&mut self, changed: &[Coordinate]) -> Vec<Coordinate> {
+        recalculate(self, changed)
+    }
+
+    pub fn cycles(&self) -> &[Vec<Coordinate>] {
+        /*
+        The circular references in the DataFrame, each given as the cells forming the loop in the order they
+        reference each other, starting from the top left cell of the loop
+         */
+        &self.cycles
+    }
+}
+
+impl Graph for DataFrame {
+    type Cell = Coordinate;
+
+    fn dependents(&self, cell: &Coordinate) -> Vec<Coordinate> {
+        self.references.get(cell).cloned().unwrap_or_default()
+    }
+
+    fn precedents(&self, cell: &Coordinate) -> Vec<Coordinate> {
+        self.precedents.get(cell).cloned().unwrap_or_default()
+    }
+
+    fn solve(&self, cell: &Coordinate) -> Value {
+        self.solve_cell(&self.data[(cell.row, cell.column)])
+    }
+
+    fn solved_mut(&mut self, cell: &Coordinate) -> &mut Value {
+        &mut self.solved[(cell.row, cell.column)]
+    }
+
+    fn forget_loops(&mut self, cells: &HashSet<Coordinate>) {
+        self.cycles.retain(|cycle| !cycle.iter().any(|coordinate| cells.contains(coordinate)));
+        self.convergence.retain(|result| !result.cells.iter().any(|coordinate| cells.contains(coordinate)));
+    }
+
+    fn solve_loop(&mut self, component: &[Coordinate], path: Vec<Coordinate>) {
+        //Every cell on the loop is a #CYCLE! error unless iterative calculation is on
+        match self.iterative {
+            Some(settings) => {
+                let (iterations, converged) = iterate(self, component, settings);
+                self.convergence.push(Convergence{cells: component.to_vec(), iterations, converged});
+            },
+            None => for coordinate in component {
+                self.solved[(coordinate.row, coordinate.column)] = Value::ERROR(ErrorKind::CYCLE);
+            }
+        }
+        self.cycles.push(path);
+    }
+}
+
+pub(crate) fn recalculate<G: Graph>(graph: &mut G, changed: &[G::Cell]) -> Vec<G::Cell> {
+    /*
+    Solves the changed cells and everything downstream of them in topological order, so every cell is solved
+    once and only after all the cells it references. Values are read from the cache of solved cells.
+     */
+
+    //Every cell reachable from the changed cells through their dependents
+    let mut affected: HashSet<G::Cell> = HashSet::new();
+    let mut stack = changed.to_vec();
+    while let Some(cell) = stack.pop() {
+        if affected.insert(cell) {
+            stack.extend(graph.dependents(&cell));
+        }
+    }
+
+    //Number of unsolved precedents of each affected cell, only the affected cells need to be waited on
+    let mut waiting: HashMap<G::Cell, usize> = affected.iter()
+        .map(|cell| (*cell, graph.precedents(cell).iter().filter(|p| affected.contains(p)).count()))
+        .collect();
+
+    //Any loops previously found through the affected cells are found again below if they still exist
+    graph.forget_loops(&affected);
+
+    let mut ready: Vec<G::Cell> = waiting.iter().filter(|(_, count)| **count == 0).map(|(c, _)| *c).collect();
+    ready.sort();
+    let mut ready = VecDeque::from(ready);
+
+    let mut order = vec![];
+    let mut solved: HashSet<G::Cell> = HashSet::new();
+    loop {
+        while let Some(cell) = ready.pop_front() {
+            let value = graph.solve(&cell);
+            *graph.solved_mut(&cell) = value;
+            solved.insert(cell);
+            order.push(cell);
+            release(graph, cell, &mut waiting, &solved, &mut ready);
+        }
+
+        //Cells still waiting are either on a loop or depend on one. Loops with nothing left to wait on outside of
+        //themselves are solved first, after that the cells depending on the loops can be solved as normal
+        let remaining: Vec<G::Cell> = waiting.iter()
+            .filter(|(cell, count)| **count > 0 && !solved.contains(cell))
+            .map(|(c, _)| *c)
+            .collect();
+        if remaining.is_empty() {
+            break
+        }
+
+        let mut found: Vec<Vec<G::Cell>> = strongly_connected(graph, &remaining).into_iter()
+            .filter(|component| component.len() > 1 || graph.dependents(&component[0]).contains(&component[0]))
+            .filter(|component| component.iter()
+                .flat_map(|cell| graph.precedents(cell))
+                .all(|precedent| !waiting.contains_key(&precedent) || solved.contains(&precedent) || component.contains(&precedent)))
+            .collect();
+        found.sort();
+
+        for component in &found {
+            let path = cycle_path(graph, component);
+            graph.solve_loop(component, path);
+            for cell in component {
+                solved.insert(*cell);
+                order.push(*cell);
+            }
+        }
+        for component in &found {
+            for cell in component {
+                release(graph, *cell, &mut waiting, &solved, &mut ready);
+            }
+        }
+    }
+
+    order
+}
+
+fn release<G: Graph>(graph: &G,
+                     cell: G::Cell,
+                     waiting: &mut HashMap<G::Cell, usize>,
+                     solved: &HashSet<G::Cell>,
+                     ready: &mut VecDeque<G::Cell>
+) {
+    //Marks a solved cell as no longer being waited on by its dependents, queueing those with nothing left to wait on
+    for dependent in graph.dependents(&cell) {
+        if let Some(count) = waiting.get_mut(&dependent) {
+            *count = count.saturating_sub(1);
+            if *count == 0 && !solved.contains(&dependent) {
+                ready.push_back(dependent);
+            }
+        }
+    }
+}
+
+fn strongly_connected<G: Graph>(graph: &G, cells: &[G::Cell]) -> Vec<Vec<G::Cell>> {
+    /*
+    Tarjan's algorithm over the given cells, following dependents. Written without recursion so long chains
+    of formulas can't overflow the stack. Each component is returned sorted.
+     */
+    let within: HashSet<G::Cell> = cells.iter().cloned().collect();
+    let successors = |cell: &G::Cell| -> Vec<G::Cell> {
+        graph.dependents(cell).into_iter().filter(|c| within.contains(c)).collect()
+    };
+
+    let mut next_index = 0;
+    let mut index: HashMap<G::Cell, usize> = HashMap::new();
+    let mut low: HashMap<G::Cell, usize> = HashMap::new();
+    let mut stack: Vec<G::Cell> = vec![];
+    let mut on_stack: HashSet<G::Cell> = HashSet::new();
+    let mut components = vec![];
+
+    let mut starts = cells.to_vec();
+    starts.sort();
+    for start in starts {
+        if index.contains_key(&start) {
+            continue
+        }
+
+        index.insert(start, next_index);
+        low.insert(start, next_index);
+        next_index += 1;
+        stack.push(start);
+        on_stack.insert(start);
+        let mut calls: Vec<(G::Cell, Vec<G::Cell>, usize)> = vec![(start, successors(&start), 0)];
+
+        while let Some((cell, next, position)) = calls.last_mut() {
+            let cell = *cell;
+            if let Some(successor) = next.get(*position).cloned() {
+                *position += 1;
+                if let Entry::Vacant(entry) = index.entry(successor) {
+                    entry.insert(next_index);
+                    low.insert(successor, next_index);
+                    next_index += 1;
+                    stack.push(successor);
+                    on_stack.insert(successor);
+                    calls.push((successor, successors(&successor), 0));
+                } else if on_stack.contains(&successor) {
+                    low.insert(cell, low[&cell].min(index[&successor]));
+                }
+                continue
+            }
+
+            calls.pop();
+            if let Some((parent, _, _)) = calls.last() {
+                low.insert(*parent, low[parent].min(low[&cell]));
+            }
+            if low[&cell] == index[&cell] {
+                let mut component = vec![];
+                while let Some(member) = stack.pop() {
+                    on_stack.remove(&member);
+                    component.push(member);
+                    if member == cell {
+                        break
+                    }
+                }
+                component.sort();
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
+
+fn cycle_path<G: Graph>(graph: &G, component: &[G::Cell]) -> Vec<G::Cell> {
+    //Shortest loop through the top left cell of a strongly connected component, found breadth first
+    let start = component[0];
+    let within: HashSet<&G::Cell> = component.iter().collect();
+    let mut previous: HashMap<G::Cell, G::Cell> = HashMap::new();
+    let mut queue = VecDeque::from(vec![start]);
+
+    while let Some(cell) = queue.pop_front() {
+        for dependent in graph.dependents(&cell) {
+            if dependent == start {
+                let mut path = vec![cell];
+                while let Some(before) = previous.get(path.last().unwrap()) {
+                    path.push(*before);
+                }
+                path.reverse();
+                return path
+            }
+            if within.contains(&dependent) && !previous.contains_key(&dependent) {
+                previous.insert(dependent, cell);
+                queue.push_back(dependent);
+            }
+        }
+    }
+
+    component.to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::test_helpers::coordinate;
+    use crate::value::Value;
+    use crate::DataFrame;
+
+    #[test]
+    fn test_references_built(){
+        let input = DataFrame::new(
+          vec![
+            vec!["3".to_string(), "=[A0]*2".to_string()],
+            vec!["=[A0]+[B0]".to_string(), "=SUM([A0:B0])".to_string()]
+          ]
+        );
+
+        assert_eq!(Some(&vec![coordinate(0, 1), coordinate(1, 0), coordinate(1, 1)]), input.references.get(&coordinate(0, 0)));
+        assert_eq!(Some(&vec![coordinate(1, 0), coordinate(1, 1)]), input.references.get(&coordinate(0, 1)));
+        assert_eq!(Some(&vec![coordinate(0, 0), coordinate(0, 1)]), input.precedents.get(&coordinate(1, 1)));
+        assert_eq!(None, input.precedents.get(&coordinate(0, 0)));
+    }
+
+    #[test]
+    fn test_set_cell_recalculates_dependents(){
+        let mut input = DataFrame::new(
+          vec![
+            vec!["3".to_string(), "=[A0]*2".to_string(), "7".to_string()],
+            vec!["=[B0]+1".to_string(), "=[C0]".to_string(), "".to_string()]
+          ]
+        );
+        assert_eq!(Some(&Value::I32(7)), input.value(coordinate(1, 0)));
+
+        //Only the edited cell and its downstream cells are solved, in dependency order
+        let order = input.set_cell(coordinate(0, 0), Value::I32(10)).unwrap();
+        assert_eq!(vec![coordinate(0, 0), coordinate(0, 1), coordinate(1, 0)], order);
+        assert_eq!(Some(&Value::I32(20)), input.value(coordinate(0, 1)));
+        assert_eq!(Some(&Value::I32(21)), input.value(coordinate(1, 0)));
+    }
+
+    #[test]
+    fn test_set_cell_relinks(){
+        let mut input = DataFrame::new(
+          vec![
+            vec!["1".to_string(), "2".to_string(), "=[A0]".to_string()]
+          ]
+        );
+
+        //C0 now depends on B0 rather than A0
+        input.set_cell(coordinate(0, 2), Value::create_from_str("=[B0]*10".to_string())).unwrap();
+        assert_eq!(Some(&Value::I32(20)), input.value(coordinate(0, 2)));
+        assert_eq!(None, input.references.get(&coordinate(0, 0)));
+
+        assert_eq!(vec![coordinate(0, 0)], input.set_cell(coordinate(0, 0), Value::I32(5)).unwrap());
+        assert_eq!(2, input.set_cell(coordinate(0, 1), Value::I32(5)).unwrap().len());
+        assert_eq!(Some(&Value::I32(50)), input.value(coordinate(0, 2)));
+    }
+
+    #[test]
+    fn test_set_cell_range_dependents(){
+        let mut input = DataFrame::new(
+          vec![
+            vec!["1".to_string(), "=SUM([A:A])".to_string()],
+            vec!["2".to_string(), "".to_string()],
+            vec!["".to_string(), "".to_string()]
+          ]
+        );
+        assert_eq!(Some(&Value::I32(3)), input.value(coordinate(0, 1)));
+
+        input.set_cell(coordinate(2, 0), Value::I32(4)).unwrap();
+        assert_eq!(Some(&Value::I32(7)), input.value(coordinate(0, 1)));
+    }
+
+    #[test]
+    fn test_set_cell_out_of_bounds(){
+        let mut input = DataFrame::new(vec![vec!["1".to_string()]]);
+        assert!(input.set_cell(coordinate(1, 0), Value::I32(1)).is_err());
+    }
+
+    #[test]
+    fn test_self_reference(){
+        let input = DataFrame::new(vec![vec!["=[A0]".to_string(), "=[A0]+1".to_string()]]);
+
+        assert_eq!(Some(&Value::ERROR(ErrorKind::CYCLE)), input.value(coordinate(0, 0)));
+        assert_eq!(Some(&Value::ERROR(ErrorKind::CYCLE)), input.value(coordinate(0, 1)));
+        assert_eq!(&[vec![coordinate(0, 0)]], input.cycles());
+    }
+
+    #[test]
+    fn test_cycle_path(){
+        //A0 -> B0 -> B1 -> A0 form a loop, C0 depends on it and A1 is independent
+        let input = DataFrame::new(
+          vec![
+            vec!["=[A1]+[B1]".to_string(), "=[A0]*2".to_string(), "=SUM([A0:B0])".to_string()],
+            vec!["5".to_string(), "=[B0]-1".to_string(), "=[A1]".to_string()]
+          ]
+        );
+
+        assert_eq!(&[vec![coordinate(0, 0), coordinate(0, 1), coordinate(1, 1)]], input.cycles());
+        for cell in [coordinate(0, 0), coordinate(0, 1), coordinate(1, 1), coordinate(0, 2)] {
+            assert_eq!(Some(&Value::ERROR(ErrorKind::CYCLE)), input.value(cell));
+        }
+        assert_eq!(Some(&Value::I32(5)), input.value(coordinate(1, 2)));
+    }
+
+    #[test]
+    fn test_breaking_a_cycle(){
+        let mut input = DataFrame::new(
+          vec![
+            vec!["=[B0]".to_string(), "=[A0]+1".to_string(), "=[B0]*10".to_string()]
+          ]
+        );
+        assert_eq!(1, input.cycles().len());
+
+        input.set_cell(coordinate(0, 0), Value::I32(1)).unwrap();
+        assert!(input.cycles().is_empty());
+        assert_eq!(Some(&Value::I32(2)), input.value(coordinate(0, 1)));
+        assert_eq!(Some(&Value::I32(20)), input.value(coordinate(0, 2)));
+
+        //And creating one again
+        input.set_cell(coordinate(0, 0), Value::create_from_str("=[C0]".to_string())).unwrap();
+        assert_eq!(&[vec![coordinate(0, 0), coordinate(0, 1), coordinate(0, 2)]], input.cycles());
+    }
+
+    #[test]
+    fn test_long_chain(){
+        //Deep chains are solved without recursion
+        let mut rows = vec![vec!["1".to_string()]];
+        for row in 1..20000 {
+            rows.push(vec![format!("=[A{}]+1", row-1)]);
+        }
+        let input = DataFrame::new(rows);
+        assert_eq!(Some(&Value::I32(20000)), input.value(coordinate(19999, 0)));
+    }
+}
+
@@ -0,0 +1,68 @@
+use std::fmt;
+
+//The kinds of error a cell can evaluate to, displayed the same way spreadsheets display them
+#[allow(clippy::upper_case_acronyms)]
+#[derive(PartialEq)]
+#[derive(Eq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub enum ErrorKind{
+    DIV0,
+    REF,
+    VALUE,
+    NAME,
+    NUM,
+    NA,
+    CYCLE
+}
+
+impl ErrorKind{
+    pub const ALL: [ErrorKind; 7] = [
+        ErrorKind::DIV0, ErrorKind::REF, ErrorKind::VALUE, ErrorKind::NAME, ErrorKind::NUM, ErrorKind::NA, ErrorKind::CYCLE
+    ];
+
+    pub fn from_code(code: &str) -> Option<ErrorKind> {
+        /*
+        Converts the displayed error code back into its ErrorKind, e.g. "#DIV/0!" -> DIV0
+         */
+        match code.to_uppercase().as_str() {
+            "#DIV/0!" => Some(ErrorKind::DIV0),
+            "#REF!" => Some(ErrorKind::REF),
+            "#VALUE!" => Some(ErrorKind::VALUE),
+            "#NAME?" => Some(ErrorKind::NAME),
+            "#NUM!" => Some(ErrorKind::NUM),
+            "#N/A" => Some(ErrorKind::NA),
+            "#CYCLE!" => Some(ErrorKind::CYCLE),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let code = match self {
+            ErrorKind::DIV0 => "#DIV/0!",
+            ErrorKind::REF => "#REF!",
+            ErrorKind::VALUE => "#VALUE!",
+            ErrorKind::NAME => "#NAME?",
+            ErrorKind::NUM => "#NUM!",
+            ErrorKind::NA => "#N/A",
+            ErrorKind::CYCLE => "#CYCLE!"
+        };
+        write!(f, "{}", code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn test_round_trip(){
+        for kind in [ErrorKind::DIV0, ErrorKind::REF, ErrorKind::VALUE, ErrorKind::NAME, ErrorKind::NUM, ErrorKind::NA, ErrorKind::CYCLE] {
+            assert_eq!(Some(kind), ErrorKind::from_code(&kind.to_string()))
+        }
+        assert_eq!(None, ErrorKind::from_code("#OOPS"))
+    }
+}
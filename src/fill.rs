@@ -0,0 +1,149 @@
+use crate::coordinate::{Anchor, Coordinate};
+use crate::error::ErrorKind;
+use crate::range::Range;
+use crate::value::Value;
+use crate::DataFrame;
+
+impl DataFrame {
+    pub fn fill(&mut self, source: Coordinate, corner_1: Coordinate, corner_2: Coordinate) -> Result<Vec<Coordinate>, String> {
+        /*
+        Pastes the cell at source into every cell between two opposite corners (inclusive). Relative rows and columns
+        of its references move by how far each cell is from source while anchored ones stay put, references moved off
+        the DataFrame become #REF!. Returns the recalculated cells in the order they were solved.
+         */
+        self.check_bounds(source)?;
+        self.check_bounds(corner_1)?;
+        self.check_bounds(corner_2)?;
+        let (start, end) = match Range::cells(corner_1, corner_2) {
+            Range::Cells(start, end) => (start, end),
+            _ => unreachable!("Range::cells always creates a range of cells")
+        };
+
+        let value = self.data[(source.row, source.column)].clone();
+        let mut targets = vec![];
+        for row in start.row..=end.row {
+            for column in start.column..=end.column {
+                let target = Coordinate{row, column};
+                let offset = (row as i64 - source.row as i64, column as i64 - source.column as i64);
+                self.data[(row, column)] = copy(&value, offset);
+                self.unlink(target);
+                self.link(target);
+                targets.push(target);
+            }
+        }
+        Ok(self.recalculate(&targets))
+    }
+}
+
+fn copy(value: &Value, offset: (i64, i64)) -> Value {
+    /*
+    The formula of value as it reads copied (rows, columns) away, including references to other sheets
+     */
+    match value {
+        Value::CELL_REFERENCE(coordinate, anchor) => match move_coordinate(*coordinate, *anchor, offset) {
+            Some(moved) => Value::CELL_REFERENCE(moved, *anchor),
+            None => Value::ERROR(ErrorKind::REF)
+        },
+        Value::RANGE(range, anchors) => match move_range(*range, *anchors, offset) {
+            Some((moved, anchors)) => Value::RANGE(moved, anchors),
+            None => Value::ERROR(ErrorKind::REF)
+        },
+        Value::SHEET_REFERENCE(sheet, reference) => match copy(reference, offset) {
+            Value::ERROR(kind) => Value::ERROR(kind),
+            moved => Value::SHEET_REFERENCE(sheet.to_owned(), Box::new(moved))
+        },
+        Value::FUNCTION(function, values) =>
+            Value::FUNCTION(function.clone(), values.iter().map(|v| copy(v, offset)).collect()),
+        other => other.clone()
+    }
+}
+
+fn move_index(index: usize, anchored: bool, offset: i64) -> Option<usize> {
+    if anchored {
+        return Some(index)
+    }
+    usize::try_from(index as i64 + offset).ok()
+}
+
+fn move_coordinate(coordinate: Coordinate, anchor: Anchor, (rows, columns): (i64, i64)) -> Option<Coordinate> {
+    Some(Coordinate{
+        row: move_index(coordinate.row, anchor.row, rows)?,
+        column: move_index(coordinate.column, anchor.column, columns)?
+    })
+}
+
+fn move_range(range: Range, [first, last]: [Anchor; 2], (rows, columns): (i64, i64)) -> Option<(Range, [Anchor; 2])> {
+    //A corner with an anchored side can be passed by the other corner, the corners are swapped back into order
+    match range {
+        Range::Cells(start, end) => {
+            let start = move_coordinate(start, first, (rows, columns))?;
+            let end = move_coordinate(end, last, (rows, columns))?;
+            let (top, bottom) = if start.row <= end.row {(first.row, last.row)} else {(last.row, first.row)};
+            let (left, right) = if start.column <= end.column {(first.column, last.column)} else {(last.column, first.column)};
+            Some((Range::cells(start, end), [Anchor{row: top, column: left}, Anchor{row: bottom, column: right}]))
+        },
+        Range::Columns(start, end) => {
+            let (start, end) = (move_index(start, first.column, columns)?, move_index(end, last.column, columns)?);
+            let anchors = if start <= end {[first, last]} else {[last, first]};
+            Some((Range::columns(start, end), anchors))
+        },
+        Range::Rows(start, end) => {
+            let (start, end) = (move_index(start, first.row, rows)?, move_index(end, last.row, rows)?);
+            let anchors = if start <= end {[first, last]} else {[last, first]};
+            Some((Range::rows(start, end), anchors))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::{coordinate, raw, sheet};
+    use crate::value::Value;
+
+    #[test]
+    fn test_fill(){
+        let mut input = sheet(&[
+            &["2", "1", "2", "3"],
+            &["10", "=[B0]*[$A$0]+[A$0]+[$A1]", "", ""],
+            &["20", "", "", ""]
+        ]);
+        input.fill(coordinate(1, 1), coordinate(1, 1), coordinate(2, 3)).unwrap();
+
+        assert_eq!("=[B0]*[$A$0]+[A$0]+[$A1]", raw(&input, 1, 1));
+        assert_eq!("=[D0]*[$A$0]+[C$0]+[$A1]", raw(&input, 1, 3));
+        assert_eq!("=[C1]*[$A$0]+[B$0]+[$A2]", raw(&input, 2, 2));
+        assert_eq!(Some(&Value::I32(14)), input.value(coordinate(1, 1)));
+        assert_eq!(Some(&Value::I32(18)), input.value(coordinate(1, 3)));
+
+        //The filled cells are solved with the cells they now reference
+        assert_eq!(Some(&Value::I32(51)), input.value(coordinate(2, 2)));
+        input.set_cell(coordinate(2, 0), Value::I32(0)).unwrap();
+        assert_eq!(Some(&Value::I32(31)), input.value(coordinate(2, 2)));
+    }
+
+    #[test]
+    fn test_fill_ranges(){
+        let mut input = sheet(&[&["1", "=SUM([$A0:A0])", "", ""], &["", "=SUM([A:$B])+SUM([0:0])", "", ""]]);
+        input.fill(coordinate(0, 1), coordinate(0, 2), coordinate(0, 3)).unwrap();
+        assert_eq!("=SUM([$A0:C0])", raw(&input, 0, 3));
+
+        //An anchored corner passed by the other corner swaps with it
+        input.fill(coordinate(1, 1), coordinate(1, 3), coordinate(1, 3)).unwrap();
+        assert_eq!("=SUM([$B:C])+SUM([0:0])", raw(&input, 1, 3));
+
+        //References moved off the DataFrame can not be solved
+        input.fill(coordinate(0, 3), coordinate(0, 0), coordinate(0, 0)).unwrap();
+        assert_eq!("=SUM(#REF!)", raw(&input, 0, 0));
+        input.fill(coordinate(1, 1), coordinate(0, 1), coordinate(0, 1)).unwrap();
+        assert_eq!("=SUM([A:$B])+SUM(#REF!)", raw(&input, 0, 1));
+
+        assert!(input.fill(coordinate(0, 1), coordinate(0, 0), coordinate(2, 0)).is_err());
+    }
+
+    #[test]
+    fn test_fill_sheet_references(){
+        let mut input = sheet(&[&["=[Data!A0]+['My Sheet'!$B$1]", ""]]);
+        input.fill(coordinate(0, 0), coordinate(0, 1), coordinate(0, 1)).unwrap();
+        assert_eq!("=[Data!B0]+['My Sheet'!$B$1]", raw(&input, 0, 1));
+    }
+}
@@ -0,0 +1,40 @@
+use crate::error::ErrorKind;
+use crate::value::{as_number, Value};
+
+pub(crate) fn abs(input: Value) -> Result<Value, ErrorKind>{
+    match as_number(input)? {
+        Value::F32(val, prec) => Ok(Value::F32(val.abs(), prec)),
+        Value::F64(val, prec) => Ok(Value::F64(val.abs(), prec)),
+        Value::DECIMAL(units, places) => units.checked_abs().map(|units| Value::DECIMAL(units, places)).ok_or(ErrorKind::NUM),
+        Value::I32(val) => Ok(Value::integer((val as i64).abs())),
+        Value::I64(val) => Ok(val.checked_abs().map_or(Value::F64((val as f64).abs(), 0), Value::I64)),
+        Value::BOOLEAN(val) => Ok(Value::I32(val as i32)),
+        _ => Err(ErrorKind::VALUE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::functions::abs::abs;
+    use crate::value::Value;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(Value::I32(3), abs(Value::I32(-3)).unwrap());
+        assert_eq!(Value::F32(1.5, 2), abs(Value::F32(-1.5, 2)).unwrap())
+    }
+
+    #[test]
+    fn test_coercion() {
+        //Empty cells are 0 and numeric text is read as its number
+        assert_eq!(Value::I32(0), abs(Value::NULL()).unwrap());
+        assert_eq!(Value::I32(2), abs(Value::STRING("-2".to_string())).unwrap());
+        assert!(abs(Value::STRING("a".to_string())).is_err())
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(Value::I64(2147483648), abs(Value::I32(i32::MIN)).unwrap());
+        assert_eq!(Value::F64(9223372036854775808.0, 0), abs(Value::I64(i64::MIN)).unwrap())
+    }
+}
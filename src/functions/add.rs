@@ -1,21 +1,34 @@
 use std::cmp::max;
-use crate::value::{get_same_form, Value};
+use crate::error::ErrorKind;
+use crate::functions::decimal::aligned;
+use crate::functions::pow::finite;
+use crate::value::{get_same_form, widen, Value};
 
-pub(crate) fn add(input_1: Value, input_2: Value) -> Result<Value, String>{
+pub(crate) fn add(input_1: Value, input_2: Value) -> Result<Value, ErrorKind>{
     // Convert to the same datatype and the one with the most precision
-    let (val_1, val_2) = get_same_form(input_1, input_2).unwrap();
+    let (val_1, val_2) = get_same_form(input_1, input_2)?;
 
-    // Sum the actual values
-    return match (val_1, val_2) {
-        (Value::F32(val_1_val,val_1_prec), Value::F32(val_2_val,val_2_prec)) => Ok(Value::F32(val_1_val+val_2_val, max(val_1_prec, val_2_prec))),
-        (Value::I32(val_1), Value::I32(val_2)) => Ok(Value::I32(val_1 + val_2)),
-        _ => Err("Not valid datatypes for addition".to_string())
-    }
+    // Sum the actual values, a sum too big for its type is promoted to the next wider type
+    let result = match (val_1, val_2) {
+        (Value::F32(val_1_val,val_1_prec), Value::F32(val_2_val,val_2_prec)) =>
+            Ok(Value::promote(val_1_val+val_2_val, widen(val_1_val)+widen(val_2_val), max(val_1_prec, val_2_prec))),
+        (Value::F64(val_1_val,val_1_prec), Value::F64(val_2_val,val_2_prec)) => Ok(Value::F64(val_1_val+val_2_val, max(val_1_prec, val_2_prec))),
+        (Value::I32(val_1), Value::I32(val_2)) => Ok(Value::integer(val_1 as i64 + val_2 as i64)),
+        (Value::I64(val_1), Value::I64(val_2)) => Ok(val_1.checked_add(val_2).map_or(Value::F64(val_1 as f64 + val_2 as f64, 0), Value::I64)),
+        //A DECIMAL is exact so there is nothing wider to promote it to
+        (Value::DECIMAL(units_1, places_1), Value::DECIMAL(units_2, places_2)) => {
+            let (units_1, units_2, places) = aligned((units_1, places_1), (units_2, places_2))?;
+            units_1.checked_add(units_2).map(|units| Value::DECIMAL(units, places)).ok_or(ErrorKind::NUM)
+        },
+        _ => Err(ErrorKind::VALUE)
+    };
+    result.and_then(finite)
 
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::error::ErrorKind;
     use crate::functions::add::add;
     use crate::value::Value;
 
@@ -62,4 +75,33 @@ mod tests {
         };
         assert_eq!("11.90".to_string(), format!("{val:.prec$}", val=val, prec=prec))
     }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(Value::I64(2147483648), add(Value::I32(i32::MAX), Value::I32(1)).unwrap());
+        assert_eq!(Value::I64(5), add(Value::I64(3000000000), Value::I64(-2999999995)).unwrap());
+        assert_eq!(Value::F64(i64::MAX as f64 + 1.0, 0), add(Value::I64(i64::MAX), Value::I32(1)).unwrap())
+    }
+
+    #[test]
+    fn test_decimal() {
+        //0.1 + 0.2 is exactly 0.3 and the places of the most precise input are kept
+        assert_eq!(Value::DECIMAL(30, 2), add(Value::DECIMAL(1, 1), Value::DECIMAL(20, 2)).unwrap());
+        assert_eq!(Value::DECIMAL(1250, 2), add(Value::DECIMAL(250, 2), Value::I32(10)).unwrap());
+        assert_eq!(Value::DECIMAL(260, 2), add(Value::DECIMAL(250, 2), Value::F32(0.1, 1)).unwrap());
+        assert!(add(Value::DECIMAL(i128::MAX, 0), Value::DECIMAL(1, 0)).is_err())
+    }
+
+    #[test]
+    fn test_wide_floats() {
+        //An F32 can not show 1234567.10 so the sum is kept as an F64
+        assert_eq!("1234567.10".to_string(), add(Value::F32(1234567.0, 0), Value::F32(0.1, 2)).unwrap().to_string());
+        assert_eq!(Value::F64(3000000000.5, 1), add(Value::I64(3000000000), Value::F32(0.5, 1)).unwrap())
+    }
+
+    #[test]
+    fn test_float_overflow() {
+        //Floats too big for an f64 are an error rather than inf
+        assert_eq!(Err(ErrorKind::NUM), add(Value::F64(f64::MAX, 0), Value::F64(f64::MAX, 0)))
+    }
 }
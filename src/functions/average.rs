@@ -0,0 +1,42 @@
+use crate::error::ErrorKind;
+use crate::functions::div::div;
+use crate::functions::numbers;
+use crate::functions::sum::sum;
+use crate::value::Value;
+
+pub(crate) fn average(values: &[Value]) -> Result<Value, ErrorKind>{
+    // Sum of the numbers divided by how many there are, empty cells are not counted
+    let numbers = numbers(values)?;
+    let total = sum(&numbers)?;
+
+    // Keep at least the precision of the inputs so averaging 2 decimal place values shows 2 decimal places
+    match (div(total.clone(), Value::I32(numbers.len() as i32))?, total) {
+        (Value::F32(val, prec), Value::F32(_, total_prec)) => Ok(Value::F32(val, prec.max(total_prec))),
+        (Value::F64(val, prec), Value::F64(_, total_prec) | Value::F32(_, total_prec)) => Ok(Value::F64(val, prec.max(total_prec))),
+        (res, _) => Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+    use crate::functions::average::average;
+    use crate::value::Value;
+
+    #[test]
+    fn test_i32() {
+        assert_eq!(Value::I32(2), average(&[Value::I32(1), Value::I32(2), Value::I32(3)]).unwrap());
+        assert_eq!(Value::F32(1.5, 1), average(&[Value::I32(1), Value::I32(2)]).unwrap())
+    }
+
+    #[test]
+    fn test_skips_null() {
+        let range = Value::ARRAY(arr2(&[[Value::F32(1.0, 2)], [Value::NULL()], [Value::I32(2)]]));
+        assert_eq!("1.50".to_string(), average(&[range]).unwrap().to_string())
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(average(&[Value::NULL()]).is_err())
+    }
+}
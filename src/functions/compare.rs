@@ -0,0 +1,85 @@
+use std::cmp::Ordering;
+use chrono::NaiveTime;
+use crate::error::ErrorKind;
+use crate::functions::decimal::aligned;
+use crate::value::{get_same_form, Value};
+
+pub(crate) fn compare(input_1: Value, input_2: Value) -> Result<Ordering, ErrorKind>{
+    /*
+    Orders two values the way spreadsheets do: numbers before text before booleans, text compared case-insensitively.
+    An empty cell is 0, empty text or FALSE depending on what it is compared with.
+     */
+    match (input_1, input_2) {
+        (Value::BOOLEAN(val_1), Value::BOOLEAN(val_2)) => Ok(val_1.cmp(&val_2)),
+        (Value::NULL(), Value::BOOLEAN(val_2)) => Ok(false.cmp(&val_2)),
+        (Value::BOOLEAN(val_1), Value::NULL()) => Ok(val_1.cmp(&false)),
+        (Value::BOOLEAN(_), Value::STRING(_)) => Ok(Ordering::Greater),
+        (Value::STRING(_), Value::BOOLEAN(_)) => Ok(Ordering::Less),
+        (Value::BOOLEAN(_), number) if number.is_number() => Ok(Ordering::Greater),
+        (number, Value::BOOLEAN(_)) if number.is_number() => Ok(Ordering::Less),
+        (Value::STRING(val_1), Value::STRING(val_2)) => Ok(val_1.to_lowercase().cmp(&val_2.to_lowercase())),
+        (Value::NULL(), Value::STRING(val_2)) => compare(Value::STRING("".to_string()), Value::STRING(val_2)),
+        (Value::STRING(val_1), Value::NULL()) => compare(Value::STRING(val_1), Value::STRING("".to_string())),
+        (Value::STRING(_), number) if number.is_number() => Ok(Ordering::Greater),
+        (number, Value::STRING(_)) if number.is_number() => Ok(Ordering::Less),
+        (Value::DATE(val_1, _), Value::DATE(val_2, _)) => Ok(val_1.cmp(&val_2)),
+        (Value::TIME(val_1, _), Value::TIME(val_2, _)) => Ok(val_1.cmp(&val_2)),
+        (Value::DATETIME(val_1, _), Value::DATETIME(val_2, _)) => Ok(val_1.cmp(&val_2)),
+        //A date is the start of its day
+        (Value::DATE(val_1, _), Value::DATETIME(val_2, _)) => Ok(val_1.and_time(NaiveTime::MIN).cmp(&val_2)),
+        (Value::DATETIME(val_1, _), Value::DATE(val_2, _)) => Ok(val_1.cmp(&val_2.and_time(NaiveTime::MIN))),
+        //Timestamps are compared as the moment they are, whatever their offsets
+        (Value::TIMESTAMP(val_1, _), Value::TIMESTAMP(val_2, _)) => Ok(val_1.cmp(&val_2)),
+        (input_1, input_2) => match get_same_form(input_1, input_2)? {
+            (Value::I32(val_1), Value::I32(val_2)) => Ok(val_1.cmp(&val_2)),
+            (Value::I64(val_1), Value::I64(val_2)) => Ok(val_1.cmp(&val_2)),
+            (Value::F32(val_1, _), Value::F32(val_2, _)) => val_1.partial_cmp(&val_2).ok_or(ErrorKind::VALUE),
+            (Value::F64(val_1, _), Value::F64(val_2, _)) => val_1.partial_cmp(&val_2).ok_or(ErrorKind::VALUE),
+            (Value::DECIMAL(units_1, places_1), Value::DECIMAL(units_2, places_2)) => {
+                let (units_1, units_2, _) = aligned((units_1, places_1), (units_2, places_2))?;
+                Ok(units_1.cmp(&units_2))
+            },
+            _ => Err(ErrorKind::VALUE)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use crate::functions::compare::compare;
+    use crate::value::Value;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(Ordering::Less, compare(Value::I32(2), Value::F32(2.5, 1)).unwrap());
+        assert_eq!(Ordering::Equal, compare(Value::F32(3.0, 1), Value::I32(3)).unwrap());
+        assert_eq!(Ordering::Greater, compare(Value::I64(3000000000), Value::I32(i32::MAX)).unwrap());
+        assert_eq!(Ordering::Less, compare(Value::F32(0.5, 1), Value::I64(3000000000)).unwrap());
+        assert_eq!(Ordering::Equal, compare(Value::DECIMAL(150, 2), Value::F32(1.5, 1)).unwrap())
+    }
+
+    #[test]
+    fn test_strings() {
+        assert_eq!(Ordering::Equal, compare(Value::STRING("Abc".to_string()), Value::STRING("aBC".to_string())).unwrap());
+        assert_eq!(Ordering::Greater, compare(Value::STRING("1".to_string()), Value::I32(5)).unwrap())
+    }
+
+    #[test]
+    fn test_booleans() {
+        assert_eq!(Ordering::Greater, compare(Value::BOOLEAN(true), Value::BOOLEAN(false)).unwrap());
+        assert_eq!(Ordering::Greater, compare(Value::BOOLEAN(false), Value::STRING("z".to_string())).unwrap());
+        assert_eq!(Ordering::Less, compare(Value::I32(1), Value::BOOLEAN(true)).unwrap());
+        assert_eq!(Ordering::Equal, compare(Value::NULL(), Value::BOOLEAN(false)).unwrap())
+    }
+
+    #[test]
+    fn test_dates() {
+        let date = |text: &str| Value::try_from_str(text).unwrap();
+        assert_eq!(Ordering::Less, compare(date("01/02/2021"), date("2021-02-02")).unwrap());
+        assert_eq!(Ordering::Equal, compare(date("2021-02-01"), date("20210201 000000")).unwrap());
+        assert_eq!(Ordering::Greater, compare(date("21:03"), date("09:30:00")).unwrap());
+        assert_eq!(Ordering::Equal, compare(date("2021-04-18T21:03:28+10:00"), date("Sun, 18 Apr 2021 11:03:28 +0000")).unwrap());
+        assert!(compare(date("21:03"), date("2021-02-01")).is_err())
+    }
+}
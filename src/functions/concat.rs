@@ -1,7 +1,8 @@
+use crate::error::ErrorKind;
 use crate::value::Value;
 
-pub(crate) fn concat(input_1: Value, input_2: Value) -> Result<Value, String>{
-    return Ok(Value::STRING(format!("{0}{1}", input_1.to_string(), input_2.to_string())))
+pub(crate) fn concat(input_1: Value, input_2: Value) -> Result<Value, ErrorKind>{
+    Ok(Value::STRING(format!("{0}{1}", input_1.display(), input_2.display())))
 }
 
 #[cfg(test)]
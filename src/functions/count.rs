@@ -0,0 +1,43 @@
+use crate::error::ErrorKind;
+use crate::value::Value;
+
+pub(crate) fn count(values: &[Value]) -> Result<Value, ErrorKind>{
+    // Number of values that are numbers, text holding a number and booleans only count when given directly
+    let total: usize = values.iter().map(|value| match value {
+        Value::ARRAY(cells) => cells.iter().filter(|v| v.is_number()).count(),
+        value if value.is_number() => 1,
+        Value::BOOLEAN(_) => 1,
+        Value::STRING(text) => Value::from_number_str(text.trim()).is_some() as usize,
+        _ => 0
+    }).sum();
+    Ok(Value::I32(total as i32))
+}
+
+pub(crate) fn counta(values: &[Value]) -> Result<Value, ErrorKind>{
+    // Number of values that are not empty
+    let total: usize = values.iter().map(|value| match value {
+        Value::ARRAY(cells) => cells.iter().filter(|v| !matches!(v, Value::NULL())).count(),
+        Value::NULL() => 0,
+        _ => 1
+    }).sum();
+    Ok(Value::I32(total as i32))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+    use crate::functions::count::{count, counta};
+    use crate::value::Value;
+
+    #[test]
+    fn test_count() {
+        let range = Value::ARRAY(arr2(&[[Value::I32(1), Value::STRING("2".to_string())], [Value::NULL(), Value::F32(2.5, 1)]]));
+        assert_eq!(Value::I32(3), count(&[range, Value::STRING("2".to_string()), Value::STRING("a".to_string())]).unwrap())
+    }
+
+    #[test]
+    fn test_counta() {
+        let range = Value::ARRAY(arr2(&[[Value::I32(1), Value::STRING("a".to_string())], [Value::NULL(), Value::F32(2.5, 1)]]));
+        assert_eq!(Value::I32(4), counta(&[range, Value::NULL(), Value::STRING("".to_string())]).unwrap())
+    }
+}
@@ -0,0 +1,163 @@
+use std::cmp::Ordering;
+use crate::error::ErrorKind;
+use crate::value::Value;
+
+//How a DECIMAL is rounded when it loses decimal places
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub(crate) enum Rounding{
+    //Halves go to the even neighbour (banker's rounding), so rounding many amounts does not drift upwards
+    HalfEven,
+    //Halves go away from zero, the same as ROUND
+    HalfUp
+}
+
+pub(crate) fn decimal(input: Value, places: Value) -> Result<Value, ErrorKind>{
+    // Converts a number, or text holding one, to an exact DECIMAL. Without places it keeps the decimal places it
+    // already shows, with them it is rounded half to even to that many places
+    let (units, own) = to_decimal(&input)?;
+    let places = match places {
+        Value::NULL() => own,
+        Value::I32(places) => usize::try_from(places).map_err(|_| ErrorKind::VALUE)?,
+        Value::I64(places) => usize::try_from(places).map_err(|_| ErrorKind::VALUE)?,
+        Value::F32(places, _) if places >= 0.0 => places.trunc() as usize,
+        Value::F64(places, _) if places >= 0.0 => places.trunc() as usize,
+        Value::ERROR(kind) => return Err(kind),
+        _ => return Err(ErrorKind::VALUE)
+    };
+    Ok(Value::DECIMAL(rescale(units, own, places, Rounding::HalfEven)?, places))
+}
+
+pub(crate) fn to_decimal(value: &Value) -> Result<(i128, usize), ErrorKind>{
+    /*
+    The units and decimal places of any number as a DECIMAL. Floats are taken as the digits they show to their
+    precision, so 0.1 is exactly 0.1 rather than the binary fraction closest to it.
+     */
+    match value {
+        Value::DECIMAL(units, places) => Ok((*units, *places)),
+        Value::I32(val) => Ok((*val as i128, 0)),
+        Value::I64(val) => Ok((*val as i128, 0)),
+        Value::F32(val, precision) => parse(&format!("{val:.prec$}", val=val, prec=precision)).ok_or(ErrorKind::NUM),
+        Value::F64(val, precision) => parse(&format!("{val:.prec$}", val=val, prec=precision)).ok_or(ErrorKind::NUM),
+        Value::BOOLEAN(val) => Ok((*val as i128, 0)),
+        Value::NULL() => Ok((0, 0)),
+        Value::STRING(text) => parse(text.trim()).ok_or(ErrorKind::VALUE),
+        Value::ERROR(kind) => Err(*kind),
+        _ => Err(ErrorKind::VALUE)
+    }
+}
+
+pub(crate) fn parse(text: &str) -> Option<(i128, usize)> {
+    //Plain decimal text such as -1234.50, the units are every digit and the places are the digits after the point
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text.strip_prefix('+').unwrap_or(text))
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if whole.is_empty() && fraction.is_empty() || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return None
+    }
+    let units = format!("{}{}", whole, fraction).parse::<i128>().ok()?;
+    Some((if negative {-units} else {units}, fraction.len()))
+}
+
+pub(crate) fn text(units: i128, places: usize) -> String {
+    //Every decimal place is shown, trailing zeros included since they are part of the amount
+    let digits = format!("{:0>width$}", units.unsigned_abs(), width=places + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - places);
+    let sign = if units < 0 {"-"} else {""};
+    if places == 0 {
+        format!("{}{}", sign, whole)
+    } else {
+        format!("{}{}.{}", sign, whole, fraction)
+    }
+}
+
+pub(crate) fn rescale(units: i128, from: usize, to: usize, rounding: Rounding) -> Result<i128, ErrorKind>{
+    //The units of the same amount with to decimal places instead of from, rounded when places are lost
+    match to.cmp(&from) {
+        Ordering::Less => Ok(divide(units, power(from - to)?, rounding)),
+        _ => units.checked_mul(power(to - from)?).ok_or(ErrorKind::NUM)
+    }
+}
+
+pub(crate) fn aligned((units_1, places_1): (i128, usize), (units_2, places_2): (i128, usize)) -> Result<(i128, i128, usize), ErrorKind>{
+    //Both amounts with the decimal places of whichever has the most
+    let places = places_1.max(places_2);
+    Ok((rescale(units_1, places_1, places, Rounding::HalfEven)?, rescale(units_2, places_2, places, Rounding::HalfEven)?, places))
+}
+
+pub(crate) fn quotient((units_1, places_1): (i128, usize), (units_2, places_2): (i128, usize)) -> Result<Value, ErrorKind>{
+    /*
+    Divides two DECIMALs keeping the decimal places of whichever has the most, the last place is rounded half to even.
+    Multiplying by 10^(places_2 + places - places_1) first keeps every digit needed before the one division.
+     */
+    if units_2 == 0 {
+        return Err(ErrorKind::DIV0)
+    }
+    let places = places_1.max(places_2);
+    let numerator = units_1.checked_mul(power(places_2 + places - places_1)?).ok_or(ErrorKind::NUM)?;
+    Ok(Value::DECIMAL(divide(numerator, units_2, Rounding::HalfEven), places))
+}
+
+pub(crate) fn divide(numerator: i128, denominator: i128, rounding: Rounding) -> i128 {
+    let quotient = numerator / denominator;
+    let remainder = (numerator % denominator).unsigned_abs();
+    let step = if (numerator < 0) != (denominator < 0) {-1} else {1};
+    match remainder.cmp(&(denominator.unsigned_abs() - remainder)) {
+        Ordering::Less => quotient,
+        Ordering::Greater => quotient + step,
+        Ordering::Equal if rounding == Rounding::HalfEven && quotient % 2 == 0 => quotient,
+        Ordering::Equal => quotient + step
+    }
+}
+
+pub(crate) fn power(places: usize) -> Result<i128, ErrorKind>{
+    //10^places, more places than an i128 can hold is a #NUM! error
+    u32::try_from(places).ok().and_then(|places| 10_i128.checked_pow(places)).ok_or(ErrorKind::NUM)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::functions::decimal::{decimal, divide, parse, quotient, text, Rounding};
+    use crate::value::Value;
+
+    #[test]
+    fn test_parse_and_text() {
+        assert_eq!(Some((-123450, 2)), parse("-1234.50"));
+        assert_eq!(Some((5, 1)), parse(".5"));
+        assert_eq!(Some((12, 0)), parse("+12"));
+        assert_eq!(None, parse("1e3"));
+        assert_eq!(None, parse("."));
+        assert_eq!("-1234.50", text(-123450, 2));
+        assert_eq!("0.05", text(5, 2));
+        assert_eq!("-0.5", text(-5, 1));
+        assert_eq!("12", text(12, 0));
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(Ok(Value::DECIMAL(1, 1)), decimal(Value::F32(0.1, 1), Value::NULL()));
+        assert_eq!(Ok(Value::DECIMAL(123456789012345678901, 2)), decimal(Value::STRING("1234567890123456789.01".to_owned()), Value::NULL()));
+        assert_eq!(Ok(Value::DECIMAL(1200, 2)), decimal(Value::I32(12), Value::I32(2)));
+        //Banker's rounding, halves go to the even neighbour
+        assert_eq!(Ok(Value::DECIMAL(2, 0)), decimal(Value::STRING("2.5".to_owned()), Value::I32(0)));
+        assert_eq!(Ok(Value::DECIMAL(-4, 0)), decimal(Value::STRING("-3.5".to_owned()), Value::I32(0)));
+        assert_eq!(Err(ErrorKind::VALUE), decimal(Value::STRING("abc".to_owned()), Value::NULL()));
+        assert_eq!(Err(ErrorKind::VALUE), decimal(Value::I32(1), Value::I32(-1)));
+    }
+
+    #[test]
+    fn test_division() {
+        assert_eq!(Ok(Value::DECIMAL(333, 2)), quotient((1000, 2), (3, 0)));
+        assert_eq!(Ok(Value::DECIMAL(-667, 2)), quotient((-2000, 2), (3, 0)));
+        assert_eq!(Ok(Value::DECIMAL(2000, 2)), quotient((1000, 2), (5, 1)));
+        assert_eq!(Err(ErrorKind::DIV0), quotient((1000, 2), (0, 2)));
+        assert_eq!(2, divide(25, 10, Rounding::HalfEven));
+        assert_eq!(3, divide(25, 10, Rounding::HalfUp));
+        assert_eq!(-3, divide(-25, 10, Rounding::HalfUp));
+    }
+}
@@ -0,0 +1,86 @@
+use crate::error::ErrorKind;
+use crate::functions::decimal::quotient;
+use crate::functions::pow::finite;
+use crate::value::{fitted_precision, get_same_form, widen, Value};
+
+pub(crate) fn div(input_1: Value, input_2: Value) -> Result<Value, ErrorKind>{
+    // Convert to the same datatype and the one with the most precision
+    let (val_1, val_2) = get_same_form(input_1, input_2)?;
+
+    // Divide the actual values, integers stay integers only when the division is exact
+    let result = match (val_1, val_2) {
+        (_, Value::I32(0) | Value::I64(0) | Value::DECIMAL(0, _)) => Err(ErrorKind::DIV0),
+        (_, Value::F32(0.0, _)) => Err(ErrorKind::DIV0),
+        (_, Value::F64(0.0, _)) => Err(ErrorKind::DIV0),
+        (Value::F32(val_1_val,_), Value::F32(val_2_val,_)) => Ok(Value::computed(val_1_val/val_2_val, widen(val_1_val)/widen(val_2_val))),
+        (Value::F64(val_1_val,_), Value::F64(val_2_val,_)) => {
+            let result = val_1_val/val_2_val;
+            Ok(Value::F64(result, fitted_precision(result)))
+        },
+        //A DECIMAL keeps the places of the most precise input and rounds the last one half to even
+        (Value::DECIMAL(units_1, places_1), Value::DECIMAL(units_2, places_2)) => quotient((units_1, places_1), (units_2, places_2)),
+        (Value::I32(val_1), Value::I32(val_2)) => match val_1 as i64 % val_2 as i64 {
+            //i32::MIN / -1 is promoted to an I64
+            0 => Ok(Value::integer(val_1 as i64 / val_2 as i64)),
+            _ => Ok(Value::computed(val_1 as f32 / val_2 as f32, val_1 as f64 / val_2 as f64))
+        },
+        (Value::I64(val_1), Value::I64(val_2)) => match val_1.checked_rem(val_2) {
+            Some(0) => Ok(val_1.checked_div(val_2).map_or(Value::F64(val_1 as f64 / val_2 as f64, 0), Value::I64)),
+            //i64::MIN % -1 overflows but divides exactly
+            None => Ok(Value::F64(val_1 as f64 / val_2 as f64, 0)),
+            Some(_) => {
+                let result = val_1 as f64 / val_2 as f64;
+                Ok(Value::F64(result, fitted_precision(result)))
+            }
+        },
+        _ => Err(ErrorKind::VALUE)
+    };
+    result.and_then(finite)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::functions::div::div;
+    use crate::value::Value;
+
+    #[test]
+    fn test_i32() {
+        assert_eq!(Value::I32(4), div(Value::I32(12), Value::I32(3)).unwrap());
+        assert_eq!(Value::F32(3.5, 1), div(Value::I32(7), Value::I32(2)).unwrap())
+    }
+
+    #[test]
+    fn test_f32() {
+        assert_eq!("0.333333".to_string(), div(Value::F32(1.0, 1), Value::I32(3)).unwrap().to_string())
+    }
+
+    #[test]
+    fn test_zero() {
+        assert_eq!(Err(ErrorKind::DIV0), div(Value::I32(1), Value::I32(0)));
+        assert_eq!(Err(ErrorKind::DIV0), div(Value::F32(1.5, 1), Value::F32(0.0, 1)));
+        assert_eq!(Err(ErrorKind::DIV0), div(Value::I32(1), Value::NULL()));
+        assert_eq!(Err(ErrorKind::DIV0), div(Value::I64(1), Value::F64(0.0, 1)))
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(Value::DECIMAL(333, 2), div(Value::DECIMAL(1000, 2), Value::I32(3)).unwrap());
+        assert_eq!(Value::DECIMAL(12, 2), div(Value::DECIMAL(25, 2), Value::I32(2)).unwrap());
+        assert_eq!(Value::DECIMAL(22, 2), div(Value::DECIMAL(45, 2), Value::I32(2)).unwrap());
+        assert_eq!(Err(ErrorKind::DIV0), div(Value::DECIMAL(25, 2), Value::DECIMAL(0, 2)))
+    }
+
+    #[test]
+    fn test_wide() {
+        assert_eq!(Value::I64(2147483648), div(Value::I32(i32::MIN), Value::I32(-1)).unwrap());
+        assert_eq!(Value::I64(1500000000), div(Value::I64(3000000000), Value::I32(2)).unwrap());
+        assert_eq!(Value::F64(1000000000.5, 1), div(Value::I64(2000000001), Value::I32(2)).unwrap());
+        assert_eq!(Value::F64(1000000000.5, 1), div(Value::I32(2000000001), Value::I32(2)).unwrap())
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(Err(ErrorKind::NUM), div(Value::F64(1e300, 0), Value::F64(1e-300, 300)))
+    }
+}
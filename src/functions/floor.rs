@@ -0,0 +1,63 @@
+use crate::error::ErrorKind;
+use crate::functions::decimal::aligned;
+use crate::value::{get_same_form, Value};
+
+pub(crate) fn floor(input: Value, significance: Value) -> Result<Value, ErrorKind>{
+    // Rounds down to the nearest multiple of the significance
+    let (val, significance) = get_same_form(input, significance)?;
+
+    match (val, significance) {
+        (_, Value::I32(0) | Value::I64(0) | Value::DECIMAL(0, _)) => Err(ErrorKind::DIV0),
+        (_, Value::F32(0.0, _)) => Err(ErrorKind::DIV0),
+        (_, Value::F64(0.0, _)) => Err(ErrorKind::DIV0),
+        (Value::F32(val, _), Value::F32(significance, prec)) => Ok(Value::F32((val/significance.abs()).floor()*significance.abs(), prec)),
+        (Value::F64(val, _), Value::F64(significance, prec)) => Ok(Value::F64((val/significance.abs()).floor()*significance.abs(), prec)),
+        (Value::I32(val), Value::I32(significance)) => flooring(val as i128, significance as i128).map(|val| Value::integer(val as i64)),
+        (Value::I64(val), Value::I64(significance)) =>
+            flooring(val as i128, significance as i128).and_then(|val| i64::try_from(val).map_err(|_| ErrorKind::NUM)).map(Value::I64),
+        (Value::DECIMAL(units, places), Value::DECIMAL(significance, significance_places)) => {
+            let (units, significance, places) = aligned((units, places), (significance, significance_places))?;
+            flooring(units, significance).map(|units| Value::DECIMAL(units, places))
+        },
+        _ => Err(ErrorKind::VALUE)
+    }
+}
+
+fn flooring(val: i128, significance: i128) -> Result<i128, ErrorKind> {
+    //The size of i128::MIN does not fit in an i128
+    let significance = significance.checked_abs().ok_or(ErrorKind::NUM)?;
+    val.div_euclid(significance).checked_mul(significance).ok_or(ErrorKind::NUM)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::functions::floor::floor;
+    use crate::value::Value;
+
+    #[test]
+    fn test_f32() {
+        assert_eq!(Value::F32(2.0, 0), floor(Value::F32(2.7, 1), Value::I32(1)).unwrap());
+        assert_eq!(Value::F32(-3.0, 0), floor(Value::F32(-2.5, 1), Value::I32(1)).unwrap());
+        assert_eq!("2.25".to_string(), floor(Value::F32(2.3, 1), Value::F32(0.25, 2)).unwrap().to_string());
+        //Only the size of the significance matters, the same as for whole numbers
+        assert_eq!(Value::F32(2.0, 0), floor(Value::F32(2.5, 1), Value::I32(-1)).unwrap());
+        assert_eq!(Value::F32(-3.0, 0), floor(Value::F32(-2.5, 1), Value::I32(-1)).unwrap());
+        assert_eq!(Value::F64(-3.0, 0), floor(Value::F64(-2.5, 1), Value::I32(-1)).unwrap())
+    }
+
+    #[test]
+    fn test_i32() {
+        assert_eq!(Value::I32(25), floor(Value::I32(27), Value::I32(5)).unwrap());
+        assert_eq!(Value::I32(-30), floor(Value::I32(-27), Value::I32(5)).unwrap());
+        assert!(floor(Value::I32(27), Value::I32(0)).is_err())
+    }
+
+    #[test]
+    fn test_i64() {
+        //The smallest i64 has no i64 size so it is floored in i128
+        assert_eq!(Value::I64(0), floor(Value::I32(5), Value::I64(i64::MIN)).unwrap());
+        assert_eq!(Value::I64(i64::MIN), floor(Value::I32(-5), Value::I64(i64::MIN)).unwrap());
+        assert_eq!(Err(ErrorKind::NUM), floor(Value::DECIMAL(5, 0), Value::DECIMAL(i128::MIN, 0)))
+    }
+}
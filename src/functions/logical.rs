@@ -0,0 +1,198 @@
+use std::cmp::Ordering;
+use crate::error::ErrorKind;
+use crate::functions::compare::compare;
+use crate::value::Value;
+use crate::FUNCTION;
+
+pub(crate) fn is_logical(function: &FUNCTION) -> bool {
+    matches!(function, FUNCTION::IF | FUNCTION::IFS | FUNCTION::AND | FUNCTION::OR | FUNCTION::NOT | FUNCTION::XOR
+        | FUNCTION::SWITCH | FUNCTION::IFERROR | FUNCTION::IFNA)
+}
+
+pub(crate) fn logical(function: &FUNCTION, arguments: &[Value], solve: &dyn Fn(&Value) -> Value) -> Result<Value, ErrorKind>{
+    /*
+    Solves a logical function, each argument is only solved with solve once it is needed so branches that are not
+    taken are never solved. Errors are only passed on from the arguments which are solved.
+     */
+    match choose(function, arguments, solve)? {
+        Value::ERROR(kind) => Err(kind),
+        value => Ok(value)
+    }
+}
+
+fn choose(function: &FUNCTION, arguments: &[Value], solve: &dyn Fn(&Value) -> Value) -> Result<Value, ErrorKind>{
+    match (function, arguments) {
+        (FUNCTION::IF, [condition, then]) => if truth(&solve(condition))? {Ok(solve(then))} else {Ok(Value::BOOLEAN(false))},
+        (FUNCTION::IF, [condition, then, otherwise]) => Ok(solve(if truth(&solve(condition))? {then} else {otherwise})),
+        (FUNCTION::IFS, _) if !arguments.is_empty() && arguments.len().is_multiple_of(2) => {
+            //Pairs of a condition and its value, the value of the first true condition is used
+            for pair in arguments.chunks(2) {
+                if truth(&solve(&pair[0]))? {
+                    return Ok(solve(&pair[1]))
+                }
+            }
+            Err(ErrorKind::NA)
+        },
+        (FUNCTION::SWITCH, [expression, cases @ ..]) if cases.len() >= 2 => {
+            //Pairs of a case and its value, optionally followed by a default used when no case matches
+            let expression = solve(expression);
+            if let Value::ERROR(kind) = expression {
+                return Err(kind)
+            }
+            for pair in cases.chunks_exact(2) {
+                let case = solve(&pair[0]);
+                if let Value::ERROR(kind) = case {
+                    return Err(kind)
+                }
+                if compare(expression.clone(), case) == Ok(Ordering::Equal) {
+                    return Ok(solve(&pair[1]))
+                }
+            }
+            match cases.chunks_exact(2).remainder() {
+                [default] => Ok(solve(default)),
+                _ => Err(ErrorKind::NA)
+            }
+        },
+        (FUNCTION::AND, [_, ..]) => {
+            //Stops at the first false value
+            for argument in arguments {
+                if truths(&solve(argument))?.contains(&false) {
+                    return Ok(Value::BOOLEAN(false))
+                }
+            }
+            Ok(Value::BOOLEAN(true))
+        },
+        (FUNCTION::OR, [_, ..]) => {
+            //Stops at the first true value
+            for argument in arguments {
+                if truths(&solve(argument))?.contains(&true) {
+                    return Ok(Value::BOOLEAN(true))
+                }
+            }
+            Ok(Value::BOOLEAN(false))
+        },
+        (FUNCTION::XOR, [_, ..]) => {
+            //True when an odd number of the values are true
+            let mut odd = false;
+            for argument in arguments {
+                odd ^= truths(&solve(argument))?.into_iter().filter(|truth| *truth).count() % 2 == 1;
+            }
+            Ok(Value::BOOLEAN(odd))
+        },
+        (FUNCTION::NOT, [value]) => Ok(Value::BOOLEAN(!truth(&solve(value))?)),
+        (FUNCTION::IFERROR, [value, fallback]) => match solve(value) {
+            Value::ERROR(_) => Ok(solve(fallback)),
+            value => Ok(value)
+        },
+        (FUNCTION::IFNA, [value, fallback]) => match solve(value) {
+            Value::ERROR(ErrorKind::NA) => Ok(solve(fallback)),
+            value => Ok(value)
+        },
+        _ => Err(ErrorKind::VALUE)
+    }
+}
+
+fn truth(value: &Value) -> Result<bool, ErrorKind>{
+    /*
+    Whether a single value is true, numbers are true when they are not 0 and empty cells are false.
+    Text is only accepted when it is TRUE or FALSE.
+     */
+    match value {
+        Value::ERROR(kind) => Err(*kind),
+        Value::BOOLEAN(val) => Ok(*val),
+        Value::I32(val) => Ok(*val != 0),
+        Value::I64(val) => Ok(*val != 0),
+        Value::F32(val, _) => Ok(*val != 0.0),
+        Value::F64(val, _) => Ok(*val != 0.0),
+        Value::DECIMAL(units, _) => Ok(*units != 0),
+        Value::NULL() => Ok(false),
+        Value::STRING(text) if text.trim().eq_ignore_ascii_case("TRUE") => Ok(true),
+        Value::STRING(text) if text.trim().eq_ignore_ascii_case("FALSE") => Ok(false),
+        _ => Err(ErrorKind::VALUE)
+    }
+}
+
+fn truths(value: &Value) -> Result<Vec<bool>, ErrorKind>{
+    /*
+    The truth of each value an argument of AND, OR or XOR holds. Only numbers and booleans inside a range are used,
+    the same as spreadsheets do, and a range with none of them is an error.
+     */
+    match value {
+        Value::ARRAY(cells) => {
+            let mut collected = vec![];
+            for cell in cells {
+                match cell {
+                    Value::ERROR(kind) => return Err(*kind),
+                    Value::BOOLEAN(_) => collected.push(truth(cell)?),
+                    number if number.is_number() => collected.push(truth(number)?),
+                    _ => {}
+                }
+            }
+            if collected.is_empty() {
+                return Err(ErrorKind::VALUE)
+            }
+            Ok(collected)
+        },
+        value => Ok(vec![truth(value)?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+    use crate::error::ErrorKind;
+    use crate::functions::logical::logical;
+    use crate::value::Value;
+    use crate::FUNCTION;
+
+    fn solved(function: FUNCTION, arguments: &[Value]) -> Result<Value, ErrorKind> {
+        logical(&function, arguments, &|value| value.clone())
+    }
+
+    #[test]
+    fn test_conditions() {
+        let error = Value::ERROR(ErrorKind::DIV0);
+        assert_eq!(Ok(Value::I32(2)), solved(FUNCTION::IF, &[Value::I32(0), error.clone(), Value::I32(2)]));
+        assert_eq!(Ok(Value::BOOLEAN(false)), solved(FUNCTION::IF, &[Value::STRING("false".to_owned()), Value::I32(1)]));
+        assert_eq!(Ok(Value::I32(1)), solved(FUNCTION::IF, &[Value::BOOLEAN(true), Value::I32(1)]));
+        assert_eq!(Err(ErrorKind::VALUE), solved(FUNCTION::IF, &[Value::STRING("a".to_owned()), Value::I32(1)]));
+        assert_eq!(Ok(Value::I32(3)), solved(FUNCTION::IFS, &[Value::I32(0), error.clone(), Value::F32(0.5, 1), Value::I32(3)]));
+        assert_eq!(Err(ErrorKind::NA), solved(FUNCTION::IFS, &[Value::I32(0), Value::I32(1)]));
+        assert_eq!(Err(ErrorKind::VALUE), solved(FUNCTION::IFS, &[Value::I32(1)]));
+    }
+
+    #[test]
+    fn test_switch() {
+        let cases = |expression: Value| vec![expression, Value::I32(1), Value::STRING("one".to_owned()), Value::STRING("B".to_owned()), Value::I32(2)];
+        assert_eq!(Ok(Value::STRING("one".to_owned())), solved(FUNCTION::SWITCH, &cases(Value::F32(1.0, 1))));
+        assert_eq!(Ok(Value::I32(2)), solved(FUNCTION::SWITCH, &cases(Value::STRING("b".to_owned()))));
+        assert_eq!(Err(ErrorKind::NA), solved(FUNCTION::SWITCH, &cases(Value::I32(3))));
+
+        let mut with_default = cases(Value::I32(3));
+        with_default.push(Value::STRING("other".to_owned()));
+        assert_eq!(Ok(Value::STRING("other".to_owned())), solved(FUNCTION::SWITCH, &with_default));
+    }
+
+    #[test]
+    fn test_and_or() {
+        let range = Value::ARRAY(arr2(&[[Value::I32(1), Value::STRING("a".to_owned())], [Value::NULL(), Value::F32(2.5, 1)]]));
+        assert_eq!(Ok(Value::BOOLEAN(true)), solved(FUNCTION::AND, &[range.clone(), Value::STRING("TRUE".to_owned())]));
+        assert_eq!(Ok(Value::BOOLEAN(false)), solved(FUNCTION::AND, &[Value::I32(0), Value::ERROR(ErrorKind::REF)]));
+        assert_eq!(Ok(Value::BOOLEAN(true)), solved(FUNCTION::OR, &[Value::I32(1), Value::ERROR(ErrorKind::REF)]));
+        assert_eq!(Err(ErrorKind::REF), solved(FUNCTION::OR, &[Value::I32(0), Value::ERROR(ErrorKind::REF)]));
+        assert_eq!(Ok(Value::BOOLEAN(false)), solved(FUNCTION::XOR, &[range, Value::BOOLEAN(false)]));
+        assert_eq!(Ok(Value::BOOLEAN(true)), solved(FUNCTION::XOR, &[Value::I32(1), Value::I32(0)]));
+        assert_eq!(Ok(Value::BOOLEAN(false)), solved(FUNCTION::NOT, &[Value::I32(5)]));
+        assert_eq!(Ok(Value::BOOLEAN(true)), solved(FUNCTION::NOT, &[Value::BOOLEAN(false)]));
+        assert_eq!(Err(ErrorKind::VALUE), solved(FUNCTION::AND, &[Value::ARRAY(arr2(&[[Value::NULL()]]))]));
+        assert_eq!(Err(ErrorKind::VALUE), solved(FUNCTION::OR, &[]));
+    }
+
+    #[test]
+    fn test_iferror() {
+        assert_eq!(Ok(Value::I32(0)), solved(FUNCTION::IFERROR, &[Value::ERROR(ErrorKind::DIV0), Value::I32(0)]));
+        assert_eq!(Ok(Value::I32(4)), solved(FUNCTION::IFERROR, &[Value::I32(4), Value::I32(0)]));
+        assert_eq!(Ok(Value::I32(0)), solved(FUNCTION::IFNA, &[Value::ERROR(ErrorKind::NA), Value::I32(0)]));
+        assert_eq!(Err(ErrorKind::DIV0), solved(FUNCTION::IFNA, &[Value::ERROR(ErrorKind::DIV0), Value::I32(0)]));
+    }
+}
@@ -0,0 +1,33 @@
+use std::cmp::Ordering;
+use crate::error::ErrorKind;
+use crate::functions::{extreme, numbers};
+use crate::value::Value;
+
+pub(crate) fn max(values: &[Value]) -> Result<Value, ErrorKind>{
+    // Largest number, 0 when there are no numbers
+    extreme(numbers(values)?, Ordering::Greater)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+    use crate::functions::max::max;
+    use crate::value::Value;
+
+    #[test]
+    fn test_i32() {
+        assert_eq!(Value::I32(7), max(&[Value::I32(1), Value::I32(7), Value::I32(-3)]).unwrap())
+    }
+
+    #[test]
+    fn test_mixed() {
+        // Mixed columns are promoted to F32 the same as ADD
+        let range = Value::ARRAY(arr2(&[[Value::I32(3)], [Value::NULL()], [Value::F32(2.5, 2)]]));
+        assert_eq!(Value::F32(3.0, 2), max(&[range]).unwrap())
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(Value::I32(0), max(&[]).unwrap())
+    }
+}
@@ -0,0 +1,27 @@
+use std::cmp::Ordering;
+use crate::error::ErrorKind;
+use crate::functions::{extreme, numbers};
+use crate::value::Value;
+
+pub(crate) fn min(values: &[Value]) -> Result<Value, ErrorKind>{
+    // Smallest number, 0 when there are no numbers
+    extreme(numbers(values)?, Ordering::Less)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+    use crate::functions::min::min;
+    use crate::value::Value;
+
+    #[test]
+    fn test_i32() {
+        assert_eq!(Value::I32(-3), min(&[Value::I32(1), Value::I32(7), Value::I32(-3)]).unwrap())
+    }
+
+    #[test]
+    fn test_mixed() {
+        let range = Value::ARRAY(arr2(&[[Value::I32(3)], [Value::NULL()], [Value::F32(2.5, 2)]]));
+        assert_eq!(Value::F32(2.5, 2), min(&[range, Value::I32(4)]).unwrap())
+    }
+}
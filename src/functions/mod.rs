@@ -0,0 +1,96 @@
+use std::cmp::Ordering;
+use crate::error::ErrorKind;
+use crate::functions::compare::compare;
+use crate::value::{get_same_form, Value};
+
+pub(crate) mod abs;
+pub(crate) mod add;
+pub(crate) mod average;
+pub(crate) mod ceil;
+pub(crate) mod compare;
+pub(crate) mod concat;
+pub(crate) mod count;
+pub(crate) mod decimal;
+pub(crate) mod div;
+pub(crate) mod floor;
+pub(crate) mod logical;
+pub(crate) mod max;
+pub(crate) mod min;
+pub(crate) mod modulo;
+pub(crate) mod mul;
+pub(crate) mod neg;
+pub(crate) mod pow;
+pub(crate) mod product;
+pub(crate) mod round;
+pub(crate) mod sqrt;
+pub(crate) mod sub;
+pub(crate) mod sum;
+
+pub(crate) fn numbers(values: &[Value]) -> Result<Vec<Value>, ErrorKind>{
+    /*
+    Collects the numbers an aggregate function works on from any mix of scalars and solved ranges.
+    Anything that is not a number inside a range is skipped, the same as spreadsheets do. Values given directly
+    are skipped when empty, text holding a number is used as that number, TRUE and FALSE are 1 and 0 and any other
+    value is an error.
+     */
+    let mut collected = vec![];
+    for value in values {
+        match value {
+            Value::ARRAY(cells) => collected.extend(cells.iter().filter(|v| v.is_number()).cloned()),
+            value if value.is_number() => collected.push(value.clone()),
+            Value::NULL() => {},
+            Value::BOOLEAN(val) => collected.push(Value::I32(*val as i32)),
+            Value::STRING(text) => match Value::from_number_str(text.trim()) {
+                Some(number) => collected.push(number),
+                None => return Err(ErrorKind::VALUE)
+            },
+            _ => return Err(ErrorKind::VALUE)
+        }
+    }
+    Ok(collected)
+}
+
+pub(crate) fn extreme(numbers: Vec<Value>, keep: Ordering) -> Result<Value, ErrorKind>{
+    /*
+    Finds the smallest (Less) or largest (Greater) number. Every number is promoted the same way as ADD,
+    so a mix of I32 and F32 returns an F32 with the most precision seen, and the same for F64.
+     */
+    let mut numbers = numbers.into_iter();
+    let first = match numbers.next() {
+        Some(first) => first,
+        None => return Ok(Value::I32(0))
+    };
+
+    numbers.try_fold(first, |current, next| {
+        let (current, next) = get_same_form(current, next)?;
+        let replace = compare(next.clone(), current.clone())? == keep;
+        Ok(match (current, next) {
+            (Value::F32(current_val, current_prec), Value::F32(next_val, next_prec)) =>
+                Value::F32(if replace {next_val} else {current_val}, current_prec.max(next_prec)),
+            (Value::F64(current_val, current_prec), Value::F64(next_val, next_prec)) =>
+                Value::F64(if replace {next_val} else {current_val}, current_prec.max(next_prec)),
+            (current, next) => if replace {next} else {current}
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+    use crate::functions::numbers;
+    use crate::value::Value;
+
+    #[test]
+    fn test_numbers() {
+        let range = Value::ARRAY(arr2(&[[Value::I32(1), Value::STRING("a".to_string())],
+                                        [Value::BOOLEAN(true), Value::F32(2.5, 1)]]));
+        let expected = vec![Value::I32(1), Value::F32(2.5, 1), Value::I32(3), Value::F32(0.5, 1), Value::I32(1)];
+        let values = [range, Value::NULL(), Value::I32(3), Value::STRING("0.5".to_string()), Value::BOOLEAN(true)];
+        assert_eq!(expected, numbers(&values).unwrap())
+    }
+
+    #[test]
+    fn test_text_argument() {
+        assert!(numbers(&[Value::STRING("a".to_string())]).is_err())
+    }
+}
@@ -0,0 +1,81 @@
+use std::cmp::max;
+use crate::error::ErrorKind;
+use crate::functions::decimal::aligned;
+use crate::functions::pow::finite;
+use crate::value::{get_same_form, Value};
+
+pub(crate) fn modulo(input_1: Value, input_2: Value) -> Result<Value, ErrorKind>{
+    // Convert to the same datatype and the one with the most precision
+    let (val_1, val_2) = get_same_form(input_1, input_2)?;
+
+    // Remainder after division, takes the sign of the divisor the same as spreadsheets (MOD(-3,2) is 1)
+    let result = match (val_1, val_2) {
+        (_, Value::I32(0) | Value::I64(0) | Value::DECIMAL(0, _)) => Err(ErrorKind::DIV0),
+        (_, Value::F32(0.0, _)) => Err(ErrorKind::DIV0),
+        (_, Value::F64(0.0, _)) => Err(ErrorKind::DIV0),
+        (Value::F32(val_1_val,val_1_prec), Value::F32(val_2_val,val_2_prec)) => {
+            Ok(Value::F32(val_1_val - val_2_val*(val_1_val/val_2_val).floor(), max(val_1_prec, val_2_prec)))
+        },
+        (Value::F64(val_1_val,val_1_prec), Value::F64(val_2_val,val_2_prec)) => {
+            Ok(Value::F64(val_1_val - val_2_val*(val_1_val/val_2_val).floor(), max(val_1_prec, val_2_prec)))
+        },
+        //The remainder is smaller than the divisor so always fits in its type
+        (Value::I32(val_1), Value::I32(val_2)) => Ok(Value::I32(remainder(val_1 as i128, val_2 as i128) as i32)),
+        (Value::I64(val_1), Value::I64(val_2)) => Ok(Value::I64(remainder(val_1 as i128, val_2 as i128) as i64)),
+        (Value::DECIMAL(units_1, places_1), Value::DECIMAL(units_2, places_2)) => {
+            let (units_1, units_2, places) = aligned((units_1, places_1), (units_2, places_2))?;
+            Ok(Value::DECIMAL(remainder(units_1, units_2), places))
+        },
+        _ => Err(ErrorKind::VALUE)
+    };
+    result.and_then(finite)
+}
+
+fn remainder(val_1: i128, val_2: i128) -> i128 {
+    // i128::MIN % -1 overflows but the remainder is still 0
+    let remainder = val_1.checked_rem(val_2).unwrap_or(0);
+    if remainder != 0 && (remainder < 0) != (val_2 < 0) {
+        remainder + val_2
+    } else {
+        remainder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::functions::modulo::modulo;
+    use crate::value::Value;
+
+    #[test]
+    fn test_i32() {
+        assert_eq!(Value::I32(1), modulo(Value::I32(7), Value::I32(3)).unwrap());
+        assert_eq!(Value::I32(1), modulo(Value::I32(-3), Value::I32(2)).unwrap());
+        assert_eq!(Value::I32(-1), modulo(Value::I32(3), Value::I32(-2)).unwrap());
+        assert_eq!(Value::I32(0), modulo(Value::I32(i32::MIN), Value::I32(-1)).unwrap())
+    }
+
+    #[test]
+    fn test_f32() {
+        assert_eq!("1.25".to_string(), modulo(Value::F32(5.25, 2), Value::I32(2)).unwrap().to_string());
+        assert_eq!("0.5".to_string(), modulo(Value::F32(-1.5, 1), Value::I32(2)).unwrap().to_string())
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(Value::DECIMAL(125, 2), modulo(Value::DECIMAL(525, 2), Value::I32(2)).unwrap());
+        assert_eq!(Value::DECIMAL(5, 1), modulo(Value::DECIMAL(-15, 1), Value::I32(2)).unwrap())
+    }
+
+    #[test]
+    fn test_zero() {
+        assert!(modulo(Value::I32(1), Value::I32(0)).is_err());
+        assert!(modulo(Value::F32(1.0, 1), Value::I32(0)).is_err())
+    }
+
+    #[test]
+    fn test_overflow() {
+        //The quotient does not fit in an f64 so there is no remainder to give
+        assert_eq!(Err(ErrorKind::NUM), modulo(Value::F64(1e300, 0), Value::F64(1e-300, 300)))
+    }
+}
@@ -0,0 +1,63 @@
+use crate::error::ErrorKind;
+use crate::functions::pow::finite;
+use crate::value::{get_same_form, widen, Value};
+
+pub(crate) fn mul(input_1: Value, input_2: Value) -> Result<Value, ErrorKind>{
+    // Convert to the same datatype and the one with the most precision
+    let (val_1, val_2) = get_same_form(input_1, input_2)?;
+
+    // Multiply the actual values, decimal places of a product are the sum of both inputs decimal places
+    // A product too big for its type is promoted the same as add
+    let result = match (val_1, val_2) {
+        (Value::F32(val_1_val,val_1_prec), Value::F32(val_2_val,val_2_prec)) =>
+            Ok(Value::promote(val_1_val*val_2_val, widen(val_1_val)*widen(val_2_val), val_1_prec+val_2_prec)),
+        (Value::F64(val_1_val,val_1_prec), Value::F64(val_2_val,val_2_prec)) => Ok(Value::F64(val_1_val*val_2_val, val_1_prec+val_2_prec)),
+        (Value::I32(val_1), Value::I32(val_2)) => Ok(Value::integer(val_1 as i64 * val_2 as i64)),
+        (Value::I64(val_1), Value::I64(val_2)) => Ok(val_1.checked_mul(val_2).map_or(Value::F64(val_1 as f64 * val_2 as f64, 0), Value::I64)),
+        (Value::DECIMAL(units_1, places_1), Value::DECIMAL(units_2, places_2)) =>
+            units_1.checked_mul(units_2).map(|units| Value::DECIMAL(units, places_1+places_2)).ok_or(ErrorKind::NUM),
+        _ => Err(ErrorKind::VALUE)
+    };
+    result.and_then(finite)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::functions::mul::mul;
+    use crate::value::Value;
+
+    #[test]
+    fn test_i32() {
+        assert_eq!(Value::I32(276), mul(Value::I32(12), Value::I32(23)).unwrap())
+    }
+
+    #[test]
+    fn test_f32_and_i32() {
+        let (val, prec) = match mul(Value::F32(1.25, 2), Value::F32(0.5, 1)).unwrap() {
+            Value::F32(val, prec) => (val, prec),
+            _ => (0.0, 0)
+        };
+        assert_eq!("0.625".to_string(), format!("{val:.prec$}", val=val, prec=prec))
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(Value::DECIMAL(27500, 4), mul(Value::DECIMAL(250, 2), Value::DECIMAL(110, 2)).unwrap());
+        assert_eq!("3.3000".to_string(), mul(Value::DECIMAL(110, 2), Value::F32(3.0, 2)).unwrap().display())
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(Value::I64(4294967294), mul(Value::I32(i32::MAX), Value::I32(2)).unwrap());
+        assert_eq!(Value::F64(2.0*i64::MAX as f64, 0), mul(Value::I64(i64::MAX), Value::I32(2)).unwrap());
+        assert_eq!("1358023.7".to_string(), mul(Value::F32(1234567.0, 0), Value::F32(1.1, 1)).unwrap().to_string())
+    }
+
+    #[test]
+    fn test_float_overflow() {
+        assert_eq!(Err(ErrorKind::NUM), mul(Value::F64(1e300, 0), Value::F64(1e300, 0)));
+        //An F32 product too big for an f32 is still kept as an F64
+        assert!(matches!(mul(Value::F32(1e38, 0), Value::F32(1e38, 0)), Ok(Value::F64(_, 0))))
+    }
+}
@@ -0,0 +1,42 @@
+use crate::error::ErrorKind;
+use crate::value::{as_number, Value};
+
+pub(crate) fn neg(input: Value) -> Result<Value, ErrorKind>{
+    match as_number(input)? {
+        Value::F32(val, prec) => Ok(Value::F32(-val, prec)),
+        Value::F64(val, prec) => Ok(Value::F64(-val, prec)),
+        Value::DECIMAL(units, places) => units.checked_neg().map(|units| Value::DECIMAL(units, places)).ok_or(ErrorKind::NUM),
+        //Negating the smallest integer of a type does not fit in it so is promoted, the same as add
+        Value::I32(val) => Ok(Value::integer(-(val as i64))),
+        Value::I64(val) => Ok(val.checked_neg().map_or(Value::F64(-(val as f64), 0), Value::I64)),
+        //--TRUE is the usual way to turn a boolean into 1 or 0
+        Value::BOOLEAN(val) => Ok(Value::I32(-(val as i32))),
+        _ => Err(ErrorKind::VALUE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::functions::neg::neg;
+    use crate::value::Value;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(Value::I32(-3), neg(Value::I32(3)).unwrap());
+        assert_eq!(Value::F32(1.5, 2), neg(Value::F32(-1.5, 2)).unwrap());
+        assert_eq!(Value::I32(-1), neg(Value::BOOLEAN(true)).unwrap());
+        assert_eq!(Value::I64(2147483648), neg(Value::I32(i32::MIN)).unwrap());
+        assert_eq!(Value::F64(9223372036854775808.0, 0), neg(Value::I64(i64::MIN)).unwrap())
+    }
+
+    #[test]
+    fn test_string() {
+        assert!(neg(Value::STRING("a".to_string())).is_err());
+        assert_eq!(Value::I32(-2), neg(Value::STRING("2".to_string())).unwrap())
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(Value::I32(0), neg(Value::NULL()).unwrap())
+    }
+}
@@ -0,0 +1,77 @@
+use crate::error::ErrorKind;
+use crate::value::{fitted_precision, get_same_form, widen, Value};
+
+pub(crate) fn pow(input_1: Value, input_2: Value) -> Result<Value, ErrorKind>{
+    // Convert to the same datatype and the one with the most precision
+    let (val_1, val_2) = get_same_form(input_1, input_2)?;
+
+    // Integers to a non-negative integer power stay integers, promoted to a wider type when too big for their own.
+    // Everything else is computed as a float
+    let result = match (val_1, val_2) {
+        (Value::I32(base), Value::I32(exponent)) if exponent >= 0 => {
+            return pow(Value::I64(base as i64), Value::I64(exponent as i64))
+        },
+        (Value::I64(base), Value::I64(exponent)) if exponent >= 0 => {
+            return match u32::try_from(exponent).ok().and_then(|exponent| base.checked_pow(exponent)) {
+                Some(result) => Ok(Value::integer(result)),
+                None => finite(Value::F64((base as f64).powf(exponent as f64), 0))
+            }
+        },
+        (Value::I32(base), Value::I32(exponent)) => Value::computed((base as f32).powi(exponent), (base as f64).powi(exponent)),
+        (Value::I64(base), Value::I64(exponent)) => {
+            let result = (base as f64).powf(exponent as f64);
+            Value::F64(result, fitted_precision(result))
+        },
+        (Value::F32(base, _), Value::F32(exponent, _)) => Value::computed(base.powf(exponent), widen(base).powf(widen(exponent))),
+        (Value::F64(base, _), Value::F64(exponent, _)) => {
+            let result = base.powf(exponent);
+            Value::F64(result, fitted_precision(result))
+        },
+        //Powers are not exact in general so a DECIMAL is raised as an F64
+        (base @ Value::DECIMAL(_, _), exponent @ Value::DECIMAL(_, _)) => {
+            let result = base.as_f64().unwrap_or_default().powf(exponent.as_f64().unwrap_or_default());
+            Value::F64(result, fitted_precision(result))
+        },
+        _ => return Err(ErrorKind::VALUE)
+    };
+
+    finite(result)
+}
+
+pub(crate) fn finite(value: Value) -> Result<Value, ErrorKind>{
+    //A float result too big to hold, or not a number at all, is #NUM! rather than inf or NaN
+    match value {
+        Value::F32(result, _) if !result.is_finite() => Err(ErrorKind::NUM),
+        Value::F64(result, _) if !result.is_finite() => Err(ErrorKind::NUM),
+        value => Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::functions::pow::pow;
+    use crate::value::Value;
+
+    #[test]
+    fn test_i32() {
+        assert_eq!(Value::I32(1024), pow(Value::I32(2), Value::I32(10)).unwrap());
+        assert_eq!(Value::F32(0.25, 2), pow(Value::I32(2), Value::I32(-2)).unwrap())
+    }
+
+    #[test]
+    fn test_f32() {
+        assert_eq!("1.5".to_string(), pow(Value::F32(2.25, 2), Value::F32(0.5, 1)).unwrap().to_string())
+    }
+
+    #[test]
+    fn test_promotion() {
+        assert_eq!(Value::I64(1 << 40), pow(Value::I32(2), Value::I32(40)).unwrap());
+        assert_eq!(Value::F64(2f64.powi(70), 0), pow(Value::I32(2), Value::I32(70)).unwrap())
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(pow(Value::F64(10.0, 0), Value::I32(400)).is_err());
+        assert!(pow(Value::F32(-8.0, 0), Value::F32(0.5, 1)).is_err())
+    }
+}
@@ -0,0 +1,36 @@
+use crate::error::ErrorKind;
+use crate::functions::mul::mul;
+use crate::functions::numbers;
+use crate::value::Value;
+
+pub(crate) fn product(values: &[Value]) -> Result<Value, ErrorKind>{
+    // Multiply every number together, with no numbers at all the product is 0 as in spreadsheets
+    let numbers = numbers(values)?;
+    if numbers.is_empty() {
+        return Ok(Value::I32(0))
+    }
+    numbers.into_iter().try_fold(Value::I32(1), mul)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+    use crate::functions::product::product;
+    use crate::value::Value;
+
+    #[test]
+    fn test_i32() {
+        assert_eq!(Value::I32(24), product(&[Value::I32(2), Value::I32(3), Value::I32(4)]).unwrap())
+    }
+
+    #[test]
+    fn test_range() {
+        let range = Value::ARRAY(arr2(&[[Value::F32(1.5, 1), Value::NULL()], [Value::I32(2), Value::F32(0.5, 1)]]));
+        assert_eq!("1.50".to_string(), product(&[range]).unwrap().to_string())
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(Value::I32(0), product(&[Value::NULL()]).unwrap())
+    }
+}
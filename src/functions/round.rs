@@ -0,0 +1,127 @@
+use crate::error::ErrorKind;
+use crate::functions::decimal::{divide, power, rescale, Rounding};
+use crate::value::{as_number, widen, Value};
+
+pub(crate) fn round(input: Value, digits: Value) -> Result<Value, ErrorKind>{
+    // Rounds half away from zero to the number of decimal places, negative digits round to the left of the decimal point
+    let digits = match as_number(digits)? {
+        Value::I32(digits) => digits,
+        Value::I64(digits) => digits.clamp(i32::MIN as i64, i32::MAX as i64) as i32,
+        Value::F32(digits, _) => digits.trunc() as i32,
+        Value::F64(digits, _) => digits.trunc() as i32,
+        _ => return Err(ErrorKind::VALUE)
+    };
+
+    match as_number(input)? {
+        Value::I32(val) if digits >= 0 => Ok(Value::I32(val)),
+        Value::I64(val) if digits >= 0 => Ok(Value::I64(val)),
+        //Rounding a whole number can carry it past the type it was, e.g. ROUND(2147483647,-1), so it is done in i128
+        Value::I32(val) => Ok(Value::integer(round_whole(val as i128, digits) as i64)),
+        Value::I64(val) => {
+            let rounded = round_whole(val as i128, digits);
+            Ok(i64::try_from(rounded).map_or(Value::F64(rounded as f64, 0), Value::I64))
+        },
+        //Rounding past the last digit a float holds changes nothing, so the value is kept as it was
+        Value::F32(val, precision) => Ok(match round_float(widen(val), digits, f32::MANTISSA_DIGITS) {
+            Some(rounded) => Value::F32(rounded as f32, digits.max(0) as usize),
+            None => Value::F32(val, precision)
+        }),
+        Value::F64(val, precision) => Ok(match round_float(val, digits, f64::MANTISSA_DIGITS) {
+            Some(rounded) => Value::F64(rounded, digits.max(0) as usize),
+            None => Value::F64(val, precision)
+        }),
+        //Rounded to the digit then shown with at least no decimal places, the same as the floats
+        Value::DECIMAL(units, places) if digits >= 0 => Ok(Value::DECIMAL(rescale(units, places, digits as usize, Rounding::HalfUp)?, digits as usize)),
+        Value::DECIMAL(units, places) => {
+            //Rounding to more digits than an i128 has is always 0
+            let digits = digits.unsigned_abs() as usize;
+            let rounded = match (power(places + digits), power(digits)) {
+                (Ok(divisor), Ok(factor)) => divide(units, divisor, Rounding::HalfUp).checked_mul(factor).ok_or(ErrorKind::NUM)?,
+                _ => 0
+            };
+            Ok(Value::DECIMAL(rounded, 0))
+        },
+        _ => Err(ErrorKind::VALUE)
+    }
+}
+
+fn round_float(val: f64, digits: i32, mantissa: u32) -> Option<f64> {
+    /*
+    Rounds half away from zero to digits decimal places. None when the value is already whole at that many places
+    for a float with the given mantissa bits, which is also the case whenever scaling it is not finite.
+     */
+    if digits < 0 {
+        //Rounding to a place larger than any float is always 0
+        let step = 10_f64.powi(digits.saturating_neg());
+        return Some(if step.is_finite() {(val / step).round() * step} else {0.0})
+    }
+    let factor = 10_f64.powi(digits);
+    let scaled = val * factor;
+    if !scaled.is_finite() || scaled.abs() >= 2_f64.powi(mantissa as i32) {
+        return None
+    }
+    Some(scaled.round() / factor)
+}
+
+fn round_whole(val: i128, digits: i32) -> i128 {
+    //Only called with negative digits, rounding to more digits than an i128 has is always 0
+    let factor = match 10_i128.checked_pow(digits.unsigned_abs()) {
+        Some(factor) => factor,
+        None => return 0
+    };
+    val.signum() * (val.abs() + factor/2) / factor * factor
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::functions::round::round;
+    use crate::value::Value;
+
+    #[test]
+    fn test_f32() {
+        assert_eq!(Value::F32(2.6, 1), round(Value::F32(2.567, 3), Value::I32(1)).unwrap());
+        assert_eq!(Value::F32(-3.0, 0), round(Value::F32(-2.5, 1), Value::I32(0)).unwrap());
+        assert_eq!(Value::F32(1200.0, 0), round(Value::F32(1234.5, 1), Value::I32(-2)).unwrap());
+        assert_eq!(Value::F32(0.0, 0), round(Value::F32(1234.5, 1), Value::I32(-400)).unwrap());
+        //Digits past what the float holds leave it unchanged, including its precision
+        assert_eq!(Value::F32(2.5, 1), round(Value::F32(2.5, 1), Value::I32(40)).unwrap());
+        assert_eq!(Value::F32(2.5, 1), round(Value::F32(2.5, 1), Value::I32(400)).unwrap());
+        assert_eq!(Value::F32(0.000123, 6), round(Value::F32(0.0001234, 7), Value::I32(6)).unwrap())
+    }
+
+    #[test]
+    fn test_i32() {
+        assert_eq!(Value::I32(15), round(Value::I32(15), Value::I32(2)).unwrap());
+        assert_eq!(Value::I32(20), round(Value::I32(15), Value::I32(-1)).unwrap());
+        assert_eq!(Value::I32(-20), round(Value::I32(-15), Value::I32(-1)).unwrap());
+        assert_eq!(Value::I32(0), round(Value::I32(15), Value::I32(-12)).unwrap());
+        assert_eq!(Value::I64(2147483650), round(Value::I32(i32::MAX), Value::I32(-1)).unwrap())
+    }
+
+    #[test]
+    fn test_decimal() {
+        //ROUND goes half away from zero, unlike division which rounds half to even
+        assert_eq!(Value::DECIMAL(13, 1), round(Value::DECIMAL(125, 2), Value::I32(1)).unwrap());
+        assert_eq!(Value::DECIMAL(-13, 1), round(Value::DECIMAL(-125, 2), Value::I32(1)).unwrap());
+        assert_eq!(Value::DECIMAL(12500, 3), round(Value::DECIMAL(1250, 2), Value::I32(3)).unwrap());
+        assert_eq!(Value::DECIMAL(1300, 0), round(Value::DECIMAL(125000, 2), Value::I32(-2)).unwrap());
+        assert_eq!(Value::DECIMAL(0, 0), round(Value::DECIMAL(125000, 2), Value::I32(-40)).unwrap())
+    }
+
+    #[test]
+    fn test_wide() {
+        assert_eq!(Value::I64(3000000000), round(Value::I64(2999999999), Value::I32(-3)).unwrap());
+        assert_eq!(Value::F64(1e19, 0), round(Value::I64(i64::MAX), Value::I32(-19)).unwrap());
+        assert_eq!(Value::F64(1234567.13, 2), round(Value::F64(1234567.125, 3), Value::I32(2)).unwrap());
+        assert_eq!(Value::F64(2.5, 1), round(Value::F64(2.5, 1), Value::I32(400)).unwrap());
+        assert_eq!(Value::F64(0.0, 0), round(Value::F64(2.5, 1), Value::I32(i32::MIN)).unwrap())
+    }
+
+    #[test]
+    fn test_coercion() {
+        //Empty cells are 0 and numeric text is read as its number, for the digits too
+        assert_eq!(Value::I32(0), round(Value::NULL(), Value::I32(0)).unwrap());
+        assert_eq!(Value::F32(2.5, 1), round(Value::STRING("2.54".to_string()), Value::STRING("1".to_string())).unwrap());
+        assert!(round(Value::STRING("a".to_string()), Value::I32(0)).is_err())
+    }
+}
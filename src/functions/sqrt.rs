@@ -0,0 +1,75 @@
+use crate::error::ErrorKind;
+use crate::value::{as_number, fitted_precision, widen, Value};
+
+pub(crate) fn sqrt(input: Value) -> Result<Value, ErrorKind>{
+    // Square root, integers stay integers only for perfect squares
+    let input = as_number(input)?;
+    match input {
+        Value::I32(val) if val < 0 => Err(ErrorKind::NUM),
+        Value::I64(val) if val < 0 => Err(ErrorKind::NUM),
+        Value::F32(val, _) if val < 0.0 => Err(ErrorKind::NUM),
+        Value::F64(val, _) if val < 0.0 => Err(ErrorKind::NUM),
+        Value::DECIMAL(units, _) if units < 0 => Err(ErrorKind::NUM),
+        Value::I32(val) => match exact_root(val as i64) {
+            Some(root) => Ok(Value::integer(root)),
+            None => Ok(Value::computed((val as f32).sqrt(), (val as f64).sqrt()))
+        },
+        Value::I64(val) => match exact_root(val) {
+            Some(root) => Ok(Value::I64(root)),
+            None => {
+                let root = (val as f64).sqrt();
+                Ok(Value::F64(root, fitted_precision(root)))
+            }
+        },
+        Value::F32(val, _) => Ok(Value::computed(val.sqrt(), widen(val).sqrt())),
+        Value::F64(val, _) => {
+            let root = val.sqrt();
+            Ok(Value::F64(root, fitted_precision(root)))
+        },
+        //Roots are not exact in general so a DECIMAL is rooted as an F64
+        Value::DECIMAL(_, _) => {
+            let root = input.as_f64().unwrap_or_default().sqrt();
+            Ok(Value::F64(root, fitted_precision(root)))
+        },
+        _ => Err(ErrorKind::VALUE)
+    }
+}
+
+fn exact_root(val: i64) -> Option<i64> {
+    //The f64 root can be one off for large numbers so the neighbours are checked too
+    let root = (val as f64).sqrt().round() as i64;
+    (root-1..=root+1).find(|root| *root >= 0 && root.checked_mul(*root) == Some(val))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::functions::sqrt::sqrt;
+    use crate::value::Value;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(Value::I32(12), sqrt(Value::I32(144)).unwrap());
+        assert_eq!(Value::F32(1.5, 1), sqrt(Value::F32(2.25, 2)).unwrap());
+        assert_eq!("1.414214".to_string(), sqrt(Value::I32(2)).unwrap().to_string())
+    }
+
+    #[test]
+    fn test_negative() {
+        assert_eq!(Err(ErrorKind::NUM), sqrt(Value::I32(-4)));
+        assert_eq!(Err(ErrorKind::NUM), sqrt(Value::F32(-0.5, 1)))
+    }
+
+    #[test]
+    fn test_coercion() {
+        assert_eq!(Value::I32(0), sqrt(Value::NULL()).unwrap());
+        assert_eq!(Value::I32(3), sqrt(Value::STRING("9".to_string())).unwrap());
+        assert_eq!(Err(ErrorKind::NUM), sqrt(Value::STRING("-9".to_string())))
+    }
+
+    #[test]
+    fn test_wide() {
+        assert_eq!(Value::I64(3037000499), sqrt(Value::I64(3037000499 * 3037000499)).unwrap());
+        assert_eq!(Value::F64(1.5, 1), sqrt(Value::F64(2.25, 2)).unwrap())
+    }
+}
@@ -0,0 +1,51 @@
+use std::cmp::max;
+use crate::error::ErrorKind;
+use crate::functions::decimal::aligned;
+use crate::functions::pow::finite;
+use crate::value::{get_same_form, widen, Value};
+
+pub(crate) fn sub(input_1: Value, input_2: Value) -> Result<Value, ErrorKind>{
+    // Convert to the same datatype and the one with the most precision
+    let (val_1, val_2) = get_same_form(input_1, input_2)?;
+
+    // Subtract the actual values, a difference too big for its type is promoted the same as add
+    let result = match (val_1, val_2) {
+        (Value::F32(val_1_val,val_1_prec), Value::F32(val_2_val,val_2_prec)) =>
+            Ok(Value::promote(val_1_val-val_2_val, widen(val_1_val)-widen(val_2_val), max(val_1_prec, val_2_prec))),
+        (Value::F64(val_1_val,val_1_prec), Value::F64(val_2_val,val_2_prec)) => Ok(Value::F64(val_1_val-val_2_val, max(val_1_prec, val_2_prec))),
+        (Value::I32(val_1), Value::I32(val_2)) => Ok(Value::integer(val_1 as i64 - val_2 as i64)),
+        (Value::I64(val_1), Value::I64(val_2)) => Ok(val_1.checked_sub(val_2).map_or(Value::F64(val_1 as f64 - val_2 as f64, 0), Value::I64)),
+        (Value::DECIMAL(units_1, places_1), Value::DECIMAL(units_2, places_2)) => {
+            let (units_1, units_2, places) = aligned((units_1, places_1), (units_2, places_2))?;
+            units_1.checked_sub(units_2).map(|units| Value::DECIMAL(units, places)).ok_or(ErrorKind::NUM)
+        },
+        _ => Err(ErrorKind::VALUE)
+    };
+    result.and_then(finite)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::functions::sub::sub;
+    use crate::value::Value;
+
+    #[test]
+    fn test_i32() {
+        assert_eq!(Value::I32(-11), sub(Value::I32(12), Value::I32(23)).unwrap())
+    }
+
+    #[test]
+    fn test_f32_and_i32() {
+        let (val, prec) = match sub(Value::F32(5.25, 2), Value::I32(3)).unwrap() {
+            Value::F32(val, prec) => (val, prec),
+            _ => (0.0, 0)
+        };
+        assert_eq!("2.25".to_string(), format!("{val:.prec$}", val=val, prec=prec))
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(Err(ErrorKind::NUM), sub(Value::F64(-f64::MAX, 0), Value::F64(f64::MAX, 0)))
+    }
+}
@@ -0,0 +1,32 @@
+use crate::error::ErrorKind;
+use crate::functions::add::add;
+use crate::functions::numbers;
+use crate::value::Value;
+
+pub(crate) fn sum(values: &[Value]) -> Result<Value, ErrorKind>{
+    // Add every number together, promoting to F32 as soon as one of them is a float
+    numbers(values)?.into_iter().try_fold(Value::I32(0), add)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+    use crate::functions::sum::sum;
+    use crate::value::Value;
+
+    #[test]
+    fn test_i32() {
+        assert_eq!(Value::I32(6), sum(&[Value::I32(1), Value::I32(2), Value::I32(3)]).unwrap())
+    }
+
+    #[test]
+    fn test_range() {
+        let range = Value::ARRAY(arr2(&[[Value::I32(1)], [Value::NULL()], [Value::F32(2.25, 2)], [Value::STRING("x".to_string())]]));
+        assert_eq!("13.25".to_string(), sum(&[range, Value::I32(10)]).unwrap().to_string())
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(Value::I32(0), sum(&[]).unwrap())
+    }
+}
@@ -0,0 +1,430 @@
+use std::fmt;
+use crate::coordinate::Coordinate;
+use crate::io::MAX_CELLS;
+use crate::value::Value;
+use crate::DataFrame;
+
+//How the bytes of a file are turned into text
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub enum Encoding{
+    Utf8,
+    Utf16LE,
+    Utf16BE,
+    Latin1
+}
+
+//The dialect of a delimited file, the default reads standard comma separated files
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct CsvOptions{
+    pub delimiter: char,
+    //Character wrapping fields which contain delimiters or new lines, None turns quoting off
+    pub quote: Option<char>,
+    //Character making the next character literal, when None a quote inside a quoted field is written twice ("")
+    pub escape: Option<char>,
+    //The first record is read as plain text, without detecting numbers or formulas
+    pub has_header: bool,
+    pub encoding: Encoding,
+    //When writing, text starting with =, +, - or @ gets a leading apostrophe so spreadsheet tools do not run it
+    pub sanitize: bool
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {delimiter: ',', quote: Some('"'), escape: None, has_header: false, encoding: Encoding::Utf8, sanitize: false}
+    }
+}
+
+//What is written for each cell when exporting
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub enum CsvContent{
+    //The solved value, as shown in the cell
+    Values,
+    //The cell as entered, formulas are written as formulas and read back the same
+    Formulas
+}
+
+impl CsvOptions{
+    pub fn tsv() -> Self {
+        Self {delimiter: '\t', ..Self::default()}
+    }
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum CsvErrorKind{
+    UnterminatedQuote,
+    UnexpectedCharacter(char),
+    InvalidEncoding,
+    //The records and the widest of them padded out make more cells than MAX_CELLS, it has no line or column
+    TooLarge(usize, usize)
+}
+
+//Lines and columns count from 1, columns are characters rather than bytes
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct CsvError{
+    pub kind: CsvErrorKind,
+    pub line: usize,
+    pub column: usize
+}
+
+impl CsvError{
+    pub fn new(kind: CsvErrorKind, line: usize, column: usize) -> Self {
+        Self {kind, line, column}
+    }
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match &self.kind {
+            CsvErrorKind::UnterminatedQuote => "quoted field is never closed".to_owned(),
+            CsvErrorKind::UnexpectedCharacter(c) => format!("unexpected character '{}' after a quoted field", c),
+            CsvErrorKind::InvalidEncoding => "invalid text for the encoding".to_owned(),
+            CsvErrorKind::TooLarge(records, fields) =>
+                return write!(f, "{} records of up to {} fields is larger than the {} cells supported", records, fields, MAX_CELLS)
+        };
+        write!(f, "{} at line {}, column {}", message, self.line, self.column)
+    }
+}
+
+impl DataFrame {
+    pub fn from_csv(input: &[u8], options: &CsvOptions) -> Result<DataFrame, CsvError> {
+        /*
+        Reads a delimited file into a DataFrame. Each field is read the same as typed input, so numbers, dates and
+        formulas are detected, and records shorter than the longest record are padded with empty cells.
+         */
+        let mut records = read(input, options)?;
+        //Every record is padded to the widest, so a few wide records can make far more cells than the file has bytes
+        let fields = records.iter().map(Vec::len).max().unwrap_or(0);
+        if records.len().checked_mul(fields).is_none_or(|cells| cells > MAX_CELLS) {
+            return Err(CsvError::new(CsvErrorKind::TooLarge(records.len(), fields), 0, 0))
+        }
+        if options.has_header {
+            //The apostrophe keeps a header such as "2023" as text
+            for field in records.iter_mut().take(1).flatten().filter(|field| !field.is_empty()) {
+                field.insert(0, '\'');
+            }
+        }
+        Ok(DataFrame::new(records))
+    }
+}
+
+impl DataFrame {
+    pub fn to_csv(&self, content: CsvContent, options: &CsvOptions) -> String {
+        /*
+        Writes the DataFrame as a delimited file, one record per row with RFC 4180 line endings.
+        The text is always UTF-8, the encoding option only applies to reading.
+         */
+        let cells = match content {
+            CsvContent::Values => &self.solved,
+            CsvContent::Formulas => &self.data
+        };
+
+        let mut output = String::new();
+        for (row, values) in cells.rows().into_iter().enumerate() {
+            let fields: Vec<String> = values.iter().enumerate()
+                .map(|(column, value)| {
+                    let text = match content {
+                        CsvContent::Values => value.display(),
+                        CsvContent::Formulas => self.input(Coordinate{row, column}).unwrap_or_default()
+                    };
+                    write_field(&sanitize(text, value, options), options)
+                })
+                .collect();
+            output.push_str(&fields.join(&options.delimiter.to_string()));
+            output.push_str("\r\n");
+        }
+        output
+    }
+}
+
+fn sanitize(text: String, value: &Value, options: &CsvOptions) -> String {
+    //Numbers such as -5 can not run as a formula so are left alone
+    let risky = text.starts_with(['=', '+', '-', '@']) && !value.is_number();
+    if options.sanitize && risky {
+        format!("'{}", text)
+    } else {
+        text
+    }
+}
+
+fn write_field(text: &str, options: &CsvOptions) -> String {
+    /*
+    Quotes a field holding a delimiter, quote or line ending, or escapes those characters when quoting is turned off
+     */
+    let special = |c: char| c == options.delimiter || c == '\r' || c == '\n' || Some(c) == options.quote || Some(c) == options.escape;
+    if !text.contains(special) {
+        return text.to_owned()
+    }
+
+    match (options.quote, options.escape) {
+        (Some(quote), escape) => {
+            let mut field = String::from(quote);
+            for c in text.chars() {
+                if c == quote || (Some(c) == escape && escape != Some(quote)) {
+                    //Without a separate escape character a quote is escaped by doubling it
+                    field.push(escape.unwrap_or(quote));
+                }
+                field.push(c);
+            }
+            field.push(quote);
+            field
+        },
+        (None, Some(escape)) => text.chars()
+            .flat_map(|c| if special(c) {vec![escape, c]} else {vec![c]})
+            .collect(),
+        (None, None) => text.to_owned()
+    }
+}
+
+pub fn read(input: &[u8], options: &CsvOptions) -> Result<Vec<Vec<String>>, CsvError> {
+    /*
+    Splits a delimited file into records of fields, without interpreting the fields
+     */
+    let text = decode(input, options.encoding)?;
+    let mut cursor = Cursor{chars: text.chars().collect(), position: 0, line: 1, column: 1};
+    let mut records = vec![];
+
+    while cursor.peek().is_some() {
+        let mut record = vec![];
+        loop {
+            record.push(field(&mut cursor, options)?);
+            match cursor.next() {
+                Some(c) if c == options.delimiter => continue,
+                Some('\r') => {
+                    if cursor.peek() == Some('\n') {
+                        cursor.next();
+                    }
+                    break
+                },
+                _ => break
+            }
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn field(cursor: &mut Cursor, options: &CsvOptions) -> Result<String, CsvError> {
+    /*
+    Reads one field, leaving the cursor on the delimiter or line ending after it
+     */
+    let mut text = String::new();
+    let ends_field = |c: char| c == options.delimiter || c == '\r' || c == '\n';
+
+    if options.quote.is_none() || cursor.peek() != options.quote {
+        while let Some(c) = cursor.peek().filter(|c| !ends_field(*c)) {
+            cursor.next();
+            match options.escape {
+                Some(escape) if c == escape && Some(escape) != options.quote => text.extend(cursor.next()),
+                _ => text.push(c)
+            }
+        }
+        return Ok(text)
+    }
+
+    let quote = cursor.next();
+    let (line, column) = (cursor.line, cursor.column - 1);
+    let unterminated = || CsvError::new(CsvErrorKind::UnterminatedQuote, line, column);
+    loop {
+        match cursor.next() {
+            None => return Err(unterminated()),
+            Some(c) if Some(c) == options.escape && options.escape != quote => text.push(cursor.next().ok_or_else(unterminated)?),
+            Some(c) if Some(c) == quote => {
+                //A doubled quote is a literal quote, unless a different escape character is used
+                if cursor.peek() == quote && options.escape.is_none_or(|escape| Some(escape) == quote) {
+                    text.extend(cursor.next());
+                } else {
+                    break
+                }
+            },
+            Some(c) => text.push(c)
+        }
+    }
+
+    match cursor.peek() {
+        Some(c) if !ends_field(c) => Err(CsvError::new(CsvErrorKind::UnexpectedCharacter(c), cursor.line, cursor.column)),
+        _ => Ok(text)
+    }
+}
+
+//Walks the characters of a file, tracking the line and column of the next character
+struct Cursor{
+    chars: Vec<char>,
+    position: usize,
+    line: usize,
+    column: usize
+}
+
+impl Cursor{
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += 1;
+
+        //"\r\n" counts as a single line ending
+        if c == '\n' || (c == '\r' && self.peek() != Some('\n')) {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+}
+
+fn decode(input: &[u8], encoding: Encoding) -> Result<String, CsvError> {
+    /*
+    Converts the bytes of a file into text, dropping a byte order mark
+     */
+    let invalid = |valid: &str| {
+        let line = valid.matches('\n').count() + 1;
+        let column = valid.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+        CsvError::new(CsvErrorKind::InvalidEncoding, line, column)
+    };
+
+    let text = match encoding {
+        Encoding::Utf8 => match std::str::from_utf8(input) {
+            Ok(text) => text.to_owned(),
+            Err(error) => return Err(invalid(&String::from_utf8_lossy(&input[..error.valid_up_to()])))
+        },
+        Encoding::Utf16LE | Encoding::Utf16BE => {
+            let units = input.chunks(2).map(|pair| match (pair, encoding) {
+                ([low, high], Encoding::Utf16LE) => Some(u16::from_le_bytes([*low, *high])),
+                ([high, low], _) => Some(u16::from_be_bytes([*high, *low])),
+                _ => None
+            });
+
+            let mut text = String::new();
+            let mut decoded = char::decode_utf16(units.map_while(|unit| unit));
+            for c in decoded.by_ref() {
+                text.push(c.map_err(|_| invalid(&text))?);
+            }
+            if !input.len().is_multiple_of(2) {
+                return Err(invalid(&text))
+            }
+            text
+        },
+        Encoding::Latin1 => input.iter().map(|byte| *byte as char).collect()
+    };
+    Ok(text.strip_prefix('\u{feff}').map(str::to_owned).unwrap_or(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coordinate::Coordinate;
+    use crate::io::csv::{read, CsvContent, CsvError, CsvErrorKind, CsvOptions, Encoding};
+    use crate::value::Value;
+    use crate::DataFrame;
+    use ndarray::arr2;
+
+    fn strings(records: &[&[&str]]) -> Vec<Vec<String>> {
+        records.iter().map(|record| record.iter().map(|field| field.to_string()).collect()).collect()
+    }
+
+    #[test]
+    fn test_read_quoted(){
+        let input = "a,\"b,c\",\"say \"\"hi\"\"\"\r\n\"two\nlines\",,3\n";
+        let expected = strings(&[&["a", "b,c", "say \"hi\""], &["two\nlines", "", "3"]]);
+        assert_eq!(expected, read(input.as_bytes(), &CsvOptions::default()).unwrap())
+    }
+
+    #[test]
+    fn test_read_dialect(){
+        let options = CsvOptions{quote: Some('\''), escape: Some('\\'), ..CsvOptions::tsv()};
+        let input = "'a\\'b'\tc\\\td\n";
+        assert_eq!(strings(&[&["a'b", "c\td"]]), read(input.as_bytes(), &options).unwrap());
+
+        let options = CsvOptions{quote: None, ..CsvOptions::default()};
+        assert_eq!(strings(&[&["\"a", "b\""]]), read("\"a,b\"".as_bytes(), &options).unwrap())
+    }
+
+    #[test]
+    fn test_read_errors(){
+        assert_eq!(Err(CsvError::new(CsvErrorKind::UnterminatedQuote, 2, 3)),
+                   read("a,b\nc,\"d,e\n".as_bytes(), &CsvOptions::default()));
+        assert_eq!(Err(CsvError::new(CsvErrorKind::UnexpectedCharacter('x'), 1, 6)),
+                   read("a,\"b\"x,c".as_bytes(), &CsvOptions::default()));
+        assert_eq!(Err(CsvError::new(CsvErrorKind::InvalidEncoding, 2, 2)),
+                   read(b"ab\nc\xff", &CsvOptions::default()));
+    }
+
+    #[test]
+    fn test_encodings(){
+        let utf16: Vec<u8> = "\u{feff}é,1".encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+        let options = CsvOptions{encoding: Encoding::Utf16LE, ..CsvOptions::default()};
+        assert_eq!(strings(&[&["é", "1"]]), read(&utf16, &options).unwrap());
+
+        let options = CsvOptions{encoding: Encoding::Latin1, ..CsvOptions::default()};
+        assert_eq!(strings(&[&["é", "1"]]), read(b"\xe9,1", &options).unwrap());
+    }
+
+    #[test]
+    fn test_from_csv(){
+        let options = CsvOptions{has_header: true, ..CsvOptions::default()};
+        let input = DataFrame::from_csv("name,2023\nx,1.5,=[B1]*2\n\ny".as_bytes(), &options).unwrap();
+        let expected = arr2(&[[Value::STRING("name".to_owned()), Value::STRING("2023".to_owned()), Value::NULL()],
+                             [Value::STRING("x".to_owned()), Value::F32(1.5, 1), Value::F32(3.0, 1)],
+                             [Value::NULL(), Value::NULL(), Value::NULL()],
+                             [Value::STRING("y".to_owned()), Value::NULL(), Value::NULL()]]);
+        assert_eq!(expected, input.evaluate(Coordinate{row:0,column:0}, Coordinate{row:3,column:2}).unwrap());
+
+        assert_eq!((0, 0), DataFrame::from_csv(b"", &CsvOptions::default()).unwrap().dim())
+    }
+
+    #[test]
+    fn test_from_csv_limit(){
+        //One wide record pads every short record after it to the same width
+        let mut input = ",".repeat(1_000_000);
+        input.push_str(&"\n1".repeat(10));
+        let error = DataFrame::from_csv(input.as_bytes(), &CsvOptions::default()).err().unwrap();
+        assert_eq!(CsvErrorKind::TooLarge(11, 1_000_001), error.kind);
+        assert_eq!("11 records of up to 1000001 fields is larger than the 2000000 cells supported", error.to_string());
+    }
+
+    #[test]
+    fn test_to_csv(){
+        let input = DataFrame::new(strings(&[&["1.50", "a,b", "=[A0]*2"], &["say \"hi\"", "two\nlines", "12"]]));
+        assert_eq!("1.50,\"a,b\",3.00\r\n\"say \"\"hi\"\"\",\"two\nlines\",12\r\n",
+                   input.to_csv(CsvContent::Values, &CsvOptions::default()));
+
+        let options = CsvOptions{quote: None, escape: Some('\\'), ..CsvOptions::tsv()};
+        assert_eq!("1.50\ta,b\t=[A0]*2\r\nsay \"hi\"\ttwo\\\nlines\t12\r\n", input.to_csv(CsvContent::Formulas, &options));
+    }
+
+    #[test]
+    fn test_csv_round_trip(){
+        //Formulas written out read back as the same cells
+        let mut input = DataFrame::new(strings(&[&["'12", "=SUM([A1:B1])&\"x,y\"", ""], &["-3", "2.50", "#N/A"]]));
+        //Amounts stay exact, in a monetary column or not
+        input.set_cell(Coordinate{row: 0, column: 2}, Value::DECIMAL(120050, 2)).unwrap();
+        let output = DataFrame::from_csv(input.to_csv(CsvContent::Formulas, &CsvOptions::default()).as_bytes(), &CsvOptions::default()).unwrap();
+        for row in 0..2 {
+            for column in 0..3 {
+                let coordinate = Coordinate{row, column};
+                assert_eq!(input.raw(coordinate), output.raw(coordinate));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sanitize(){
+        let input = DataFrame::new(strings(&[&["'=HYPERLINK(\"x\")", "'@SUM", "'+1", "-1", "=1+1", "'-", "text"]]));
+        let options = CsvOptions{sanitize: true, ..CsvOptions::default()};
+        assert_eq!("\"'=HYPERLINK(\"\"x\"\")\",'@SUM,'+1,-1,2,'-,text\r\n", input.to_csv(CsvContent::Values, &options));
+        assert_eq!("\"'=HYPERLINK(\"\"x\"\")\",'@SUM,'+1,-1,'=1+1,'-,text\r\n", input.to_csv(CsvContent::Formulas, &options));
+    }
+}
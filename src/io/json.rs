@@ -0,0 +1,380 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+use crate::coordinate::Coordinate;
+use crate::datetime::DateParsing;
+use crate::error::ErrorKind;
+use crate::functions::decimal::parse;
+use crate::io::MAX_CELLS;
+use crate::iteration::IterativeCalculation;
+use crate::table::DEFAULT_MAX_WIDTH;
+use crate::value::Value;
+use crate::DataFrame;
+
+//Version of the document written by to_json, increase it and add a migration whenever the schema changes
+pub const VERSION: u32 = 1;
+
+//Each migration upgrades a document by one version, the first upgrades version 1 to 2
+type Migration = fn(serde_json::Value) -> Result<serde_json::Value, DocumentError>;
+const MIGRATIONS: [Migration; 0] = [];
+
+//Timestamps of date and time cells are stored in one format per type so they load the same whatever format they
+//are shown in, a TIMESTAMP is stored as RFC 3339
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M:%S%.f";
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum DocumentError{
+    //Not valid JSON or not the shape of a document
+    Json(String),
+    //Written by a newer version of this crate
+    UnsupportedVersion(u32),
+    //More rows and columns than MAX_CELLS allows
+    TooLarge(usize, usize),
+    InvalidCell(Coordinate, String)
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DocumentError::Json(message) => write!(f, "invalid document, {}", message),
+            DocumentError::UnsupportedVersion(version) =>
+                write!(f, "document version {} is newer than the supported version {}", version, VERSION),
+            DocumentError::TooLarge(rows, columns) =>
+                write!(f, "document of {} rows and {} columns is larger than the {} cells supported", rows, columns, MAX_CELLS),
+            DocumentError::InvalidCell(coordinate, message) => write!(f, "invalid cell {}, {}", coordinate, message)
+        }
+    }
+}
+
+impl From<serde_json::Error> for DocumentError {
+    fn from(error: serde_json::Error) -> Self {
+        DocumentError::Json(error.to_string())
+    }
+}
+
+#[derive(Serialize)]
+#[derive(Deserialize)]
+struct Document{
+    version: u32,
+    rows: usize,
+    columns: usize,
+    #[serde(default)]
+    metadata: BTreeMap<String, String>,
+    #[serde(default)]
+    settings: Settings,
+    //Only cells holding something are stored
+    cells: Vec<Cell>
+}
+
+#[derive(Serialize)]
+#[derive(Deserialize)]
+struct Settings{
+    max_width: usize,
+    iterative: Option<IterativeCalculation>,
+    //Decimal places of each monetary column, left out when there are none
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    monetary: BTreeMap<usize, usize>,
+    //The date formats input is read with, left out when they are the defaults
+    #[serde(default, skip_serializing_if = "default_dates")]
+    dates: DateParsing
+}
+
+fn default_dates(dates: &DateParsing) -> bool {
+    *dates == DateParsing::default()
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings{max_width: DEFAULT_MAX_WIDTH, iterative: None, monetary: BTreeMap::new(), dates: DateParsing::default()}
+    }
+}
+
+#[derive(Serialize)]
+#[derive(Deserialize)]
+struct Cell{
+    row: usize,
+    column: usize,
+    #[serde(rename = "type")]
+    kind: CellType,
+    //The cell as typed, for text this is the text itself and for floats every digit of the value
+    input: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    precision: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timestamp: Option<String>
+}
+
+#[derive(Serialize)]
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum CellType{
+    I32,
+    I64,
+    F32,
+    F64,
+    Decimal,
+    String,
+    Boolean,
+    Date,
+    Time,
+    Datetime,
+    Timestamp,
+    Error,
+    Formula
+}
+
+impl DataFrame {
+    pub fn to_json(&self) -> String {
+        /*
+        Saves the DataFrame as a versioned JSON document holding every cell as entered along with its type
+         */
+        let cells = self.data.indexed_iter()
+            .filter(|(_, value)| **value != Value::NULL())
+            .map(|((row, column), value)| cell_document(row, column, value))
+            .collect();
+
+        let document = Document{
+            version: VERSION,
+            rows: self.data.nrows(),
+            columns: self.data.ncols(),
+            metadata: self.metadata.clone(),
+            settings: Settings{max_width: self.max_width, iterative: self.iterative, monetary: self.monetary.clone(), dates: self.dates.clone()},
+            cells
+        };
+        serde_json::to_string(&document).expect("a document always serializes")
+    }
+
+    pub fn from_json(text: &str) -> Result<DataFrame, DocumentError> {
+        /*
+        Loads a DataFrame saved by to_json, upgrading documents written by older versions first
+         */
+        let document: Document = serde_json::from_value(migrate(serde_json::from_str(text)?)?)?;
+        if document.rows.checked_mul(document.columns).is_none_or(|cells| cells > MAX_CELLS) {
+            return Err(DocumentError::TooLarge(document.rows, document.columns))
+        }
+
+        let mut data = Array2::<Value>::default((document.rows, document.columns));
+        for cell in document.cells {
+            let coordinate = Coordinate{row: cell.row, column: cell.column};
+            let value = cell_value(&cell).map_err(|message| DocumentError::InvalidCell(coordinate, message))?;
+            let slot = data.get_mut((cell.row, cell.column))
+                .ok_or_else(|| DocumentError::InvalidCell(coordinate, "outside of the document dimensions".to_owned()))?;
+            *slot = value;
+        }
+
+        let mut df = DataFrame::from_values(data);
+        df.metadata = document.metadata;
+        df.monetary = document.settings.monetary;
+        df.dates = document.settings.dates;
+        df.set_max_width(document.settings.max_width);
+        if document.settings.iterative.is_some() {
+            df.set_iterative_calculation(document.settings.iterative);
+        }
+        Ok(df)
+    }
+}
+
+fn migrate(mut document: serde_json::Value) -> Result<serde_json::Value, DocumentError> {
+    /*
+    Upgrades a document one version at a time until it matches VERSION
+     */
+    let version = document.get("version").and_then(serde_json::Value::as_u64)
+        .ok_or_else(|| DocumentError::Json("missing version".to_owned()))? as u32;
+    if version == 0 || version > VERSION {
+        return Err(DocumentError::UnsupportedVersion(version))
+    }
+    for migration in &MIGRATIONS[(version - 1) as usize..] {
+        document = migration(document)?;
+    }
+    Ok(document)
+}
+
+fn cell_document(row: usize, column: usize, value: &Value) -> Cell {
+    let mut cell = Cell{row, column, kind: CellType::Formula, input: value.to_string(), precision: None, format: None, timestamp: None};
+    match value {
+        Value::I32(_) => cell.kind = CellType::I32,
+        Value::I64(_) => cell.kind = CellType::I64,
+        //Floats keep every digit, the precision they are shown with is stored on its own
+        Value::F32(val, precision) => {
+            cell.kind = CellType::F32;
+            cell.input = val.to_string();
+            cell.precision = Some(*precision);
+        },
+        Value::F64(val, precision) => {
+            cell.kind = CellType::F64;
+            cell.input = val.to_string();
+            cell.precision = Some(*precision);
+        },
+        Value::DECIMAL(_, places) => {
+            cell.kind = CellType::Decimal;
+            cell.input = value.display();
+            cell.precision = Some(*places);
+        },
+        Value::STRING(text) => {
+            cell.kind = CellType::String;
+            cell.input = text.to_owned();
+        },
+        Value::DATE(date, format) => {
+            cell.kind = CellType::Date;
+            cell.format = Some(format.to_owned());
+            cell.timestamp = Some(date.format(DATE_FORMAT).to_string());
+        },
+        Value::TIME(time, format) => {
+            cell.kind = CellType::Time;
+            cell.format = Some(format.to_owned());
+            cell.timestamp = Some(time.format(TIME_FORMAT).to_string());
+        },
+        Value::DATETIME(datetime, format) => {
+            cell.kind = CellType::Datetime;
+            cell.format = Some(format.to_owned());
+            cell.timestamp = Some(datetime.format(TIMESTAMP_FORMAT).to_string());
+        },
+        Value::TIMESTAMP(timestamp, format) => {
+            cell.kind = CellType::Timestamp;
+            cell.format = Some(format.to_owned());
+            cell.timestamp = Some(timestamp.to_rfc3339());
+        },
+        Value::BOOLEAN(_) => cell.kind = CellType::Boolean,
+        Value::ERROR(_) => cell.kind = CellType::Error,
+        _ => {}
+    }
+    cell
+}
+
+fn cell_value(cell: &Cell) -> Result<Value, String> {
+    let input = cell.input.as_str();
+    match cell.kind {
+        CellType::I32 => input.parse().map(Value::I32).map_err(|_| format!("'{}' is not an i32", input)),
+        CellType::I64 => input.parse().map(Value::I64).map_err(|_| format!("'{}' is not an i64", input)),
+        CellType::F32 => {
+            let precision = cell.precision.ok_or("f32 cell is missing its precision")?;
+            input.parse().map(|val| Value::F32(val, precision)).map_err(|_| format!("'{}' is not an f32", input))
+        },
+        CellType::F64 => {
+            let precision = cell.precision.ok_or("f64 cell is missing its precision")?;
+            input.parse().map(|val| Value::F64(val, precision)).map_err(|_| format!("'{}' is not an f64", input))
+        },
+        CellType::Decimal => {
+            let places = cell.precision.ok_or("decimal cell is missing its precision")?;
+            parse(input).filter(|(_, own)| *own == places)
+                .map(|(units, _)| Value::DECIMAL(units, places)).ok_or_else(|| format!("'{}' is not a decimal", input))
+        },
+        CellType::String => Ok(Value::STRING(input.to_owned())),
+        CellType::Boolean => Value::from_boolean_str(input).ok_or_else(|| format!("'{}' is not a boolean", input)),
+        CellType::Date => {
+            let (format, timestamp) = dated(cell, "date")?;
+            NaiveDate::parse_from_str(timestamp, DATE_FORMAT).map(|date| Value::DATE(date, format))
+                .map_err(|_| format!("'{}' is not a date", timestamp))
+        },
+        CellType::Time => {
+            let (format, timestamp) = dated(cell, "time")?;
+            NaiveTime::parse_from_str(timestamp, TIME_FORMAT).map(|time| Value::TIME(time, format))
+                .map_err(|_| format!("'{}' is not a time", timestamp))
+        },
+        CellType::Datetime => {
+            let (format, timestamp) = dated(cell, "datetime")?;
+            NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).map(|datetime| Value::DATETIME(datetime, format))
+                .map_err(|_| format!("'{}' is not a timestamp", timestamp))
+        },
+        CellType::Timestamp => {
+            let (format, timestamp) = dated(cell, "timestamp")?;
+            DateTime::parse_from_rfc3339(timestamp).map(|timestamp| Value::TIMESTAMP(timestamp, format))
+                .map_err(|_| format!("'{}' is not a timestamp", timestamp))
+        },
+        CellType::Error => ErrorKind::from_code(input).map(Value::ERROR).ok_or_else(|| format!("'{}' is not an error code", input)),
+        CellType::Formula => match Value::try_from_str(input) {
+            Ok(value @ (Value::FUNCTION(_, _) | Value::CELL_REFERENCE(_, _) | Value::RANGE(_, _) | Value::SHEET_REFERENCE(_, _))) => Ok(value),
+            Ok(_) => Err(format!("'{}' is not a formula", input)),
+            Err(error) => Err(error.to_string())
+        }
+    }
+}
+
+fn dated<'a>(cell: &'a Cell, kind: &str) -> Result<(String, &'a str), String> {
+    //The display format and stored timestamp of a date or time cell
+    let format = cell.format.as_ref().ok_or_else(|| format!("{} cell is missing its format", kind))?;
+    let timestamp = cell.timestamp.as_ref().ok_or_else(|| format!("{} cell is missing its timestamp", kind))?;
+    Ok((format.to_owned(), timestamp.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coordinate::Coordinate;
+    use crate::datetime::{DateFormat, DateOrder, DateParsing};
+    use crate::io::json::DocumentError;
+    use crate::iteration::IterativeCalculation;
+    use crate::value::Value;
+    use crate::DataFrame;
+
+    #[test]
+    fn test_round_trip(){
+        let mut input = DataFrame::new(vec![
+            vec!["1".to_string(), "2.50".to_string(), "'007".to_string(), "".to_string()],
+            vec!["20230425 130000".to_string(), "#N/A".to_string(), "=SUM([A0:B0])&\"x\"".to_string(), "=[C1]".to_string()],
+            vec!["=[A2]+1".to_string(), "TRUE".to_string(), "'FALSE".to_string(), "=[A0]>2".to_string()],
+            vec!["3000000000".to_string(), "1234567.10".to_string(), "=[A3]*4".to_string(), "".to_string()],
+            vec!["18/04/2021".to_string(), "21:03:28.5".to_string(), "2021-04-18T21:03:28+10:00".to_string(), "Sun, 18 Apr 2021 21:03:28 +1000".to_string()]
+        ]);
+        input.set_metadata("title", "Budget");
+        input.set_max_width(8);
+        input.set_iterative_calculation(Some(IterativeCalculation{max_iterations: 5, epsilon: 0.5}));
+        input.set_monetary(1, Some(2)).unwrap();
+        input.set_date_parsing(DateParsing{formats: vec![DateFormat::Iso8601, DateFormat::MonthDayYear], order: DateOrder::MonthFirst});
+        //Values with more digits than they are shown with, such as those read from xlsx files
+        input.set_cell(Coordinate{row: 0, column: 3}, Value::F64(1.2345, 2)).unwrap();
+        input.set_cell(Coordinate{row: 3, column: 3}, Value::F32(0.1234567, 1)).unwrap();
+
+        let output = DataFrame::from_json(&input.to_json()).unwrap();
+        assert_eq!(input.data, output.data);
+        assert_eq!(input.solved, output.solved);
+        assert_eq!(input.metadata(), output.metadata());
+        assert_eq!(input.max_width, output.max_width);
+        assert_eq!(input.iterative, output.iterative);
+        assert_eq!(input.monetary(), output.monetary());
+        assert_eq!(input.date_parsing(), output.date_parsing());
+        assert_eq!(input.to_json(), output.to_json());
+    }
+
+    #[test]
+    fn test_document(){
+        let input = DataFrame::new(vec![vec!["2.50".to_string(), "=[A0]*2".to_string()]]);
+        assert_eq!(
+            concat!(
+                r#"{"version":1,"rows":1,"columns":2,"metadata":{},"settings":{"max_width":20,"iterative":null},"cells":["#,
+                r#"{"row":0,"column":0,"type":"f32","input":"2.5","precision":2},"#,
+                r#"{"row":0,"column":1,"type":"formula","input":"=[A0]*2"}]}"#
+            ),
+            input.to_json()
+        );
+
+        //Only the version, dimensions and cells are required
+        let output = DataFrame::from_json(r#"{"version":1,"rows":1,"columns":1,"cells":[{"row":0,"column":0,"type":"i32","input":"4"}]}"#).unwrap();
+        assert_eq!(Some(&Value::I32(4)), output.value(Coordinate{row:0, column:0}));
+    }
+
+    #[test]
+    fn test_errors(){
+        assert_eq!(Err(DocumentError::UnsupportedVersion(2)), DataFrame::from_json(r#"{"version":2}"#).map(|_| ()));
+        assert!(matches!(DataFrame::from_json("{"), Err(DocumentError::Json(_))));
+
+        let outside = r#"{"version":1,"rows":1,"columns":1,"cells":[{"row":1,"column":0,"type":"i32","input":"4"}]}"#;
+        assert!(matches!(DataFrame::from_json(outside), Err(DocumentError::InvalidCell(Coordinate{row:1, column:0}, _))));
+
+        let formula = r#"{"version":1,"rows":1,"columns":1,"cells":[{"row":0,"column":0,"type":"formula","input":"=1+"}]}"#;
+        assert!(matches!(DataFrame::from_json(formula), Err(DocumentError::InvalidCell(_, _))));
+
+        //Dimensions are checked before anything is allocated for them
+        let huge = r#"{"version":1,"rows":100000,"columns":100000,"cells":[]}"#;
+        assert_eq!(Err(DocumentError::TooLarge(100000, 100000)), DataFrame::from_json(huge).map(|_| ()));
+        let overflowing = r#"{"version":1,"rows":4294967296,"columns":4294967296,"cells":[]}"#;
+        assert_eq!(Err(DocumentError::TooLarge(4294967296, 4294967296)), DataFrame::from_json(overflowing).map(|_| ()));
+    }
+}
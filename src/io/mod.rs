@@ -0,0 +1,6 @@
+pub mod csv;
+pub mod xlsx;
+pub mod json;
+
+//Most cells a file is read into, a sheet reaching further than this is rejected rather than running out of memory
+pub(crate) const MAX_CELLS: usize = 2_000_000;
@@ -0,0 +1,1254 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Cursor, Read, Write};
+use std::sync::LazyLock;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Timelike};
+use ndarray::Array2;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use regex::Regex;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use crate::coordinate::Coordinate;
+use crate::error::ErrorKind;
+use crate::io::MAX_CELLS;
+use crate::parser;
+use crate::parser::printer::{print_excel, sheet_name};
+use crate::value::{fitted_precision, Value};
+use crate::workbook::{valid_sheet_name, WorkbookError};
+use crate::DataFrame;
+
+//Format used for dates stored as text rather than serial numbers
+const DATETIME_FORMAT: &str = "%Y%m%d %H%M%S";
+
+//Largest part of the archive read, far more than the XML of any sheet within MAX_CELLS
+const MAX_PART_SIZE: u64 = 1 << 30;
+
+//A1 style references, whole columns such as A and whole rows such as 1 in spreadsheet formulas
+static REFERENCE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\$?)([A-Za-z]{1,3})(\$?)(\d+)$").unwrap());
+static COLUMN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\$?)([A-Za-z]{1,3})$").unwrap());
+static ROW: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\$?)(\d+)$").unwrap());
+
+//Custom number formats in a workbook are numbered from here, lower ids are built in
+const FIRST_CUSTOM_FORMAT: usize = 164;
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum XlsxError{
+    //The file is not a readable zip archive
+    Archive(String),
+    //A part of the workbook is not well formed XML
+    Xml(String),
+    MissingPart(String),
+    InvalidCell(String),
+    InvalidSheetName(String)
+}
+
+impl fmt::Display for XlsxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            XlsxError::Archive(message) => write!(f, "invalid xlsx archive, {}", message),
+            XlsxError::Xml(message) => write!(f, "invalid xlsx content, {}", message),
+            XlsxError::MissingPart(part) => write!(f, "xlsx file is missing {}", part),
+            XlsxError::InvalidCell(cell) => write!(f, "invalid cell {}", cell),
+            XlsxError::InvalidSheetName(name) => write!(f, "'{}' can not be used as a sheet name", name)
+        }
+    }
+}
+
+impl From<zip::result::ZipError> for XlsxError {
+    fn from(error: zip::result::ZipError) -> Self {
+        XlsxError::Archive(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for XlsxError {
+    fn from(error: std::io::Error) -> Self {
+        XlsxError::Archive(error.to_string())
+    }
+}
+
+impl From<quick_xml::Error> for XlsxError {
+    fn from(error: quick_xml::Error) -> Self {
+        XlsxError::Xml(error.to_string())
+    }
+}
+
+impl From<quick_xml::events::attributes::AttrError> for XlsxError {
+    fn from(error: quick_xml::events::attributes::AttrError) -> Self {
+        XlsxError::Xml(error.to_string())
+    }
+}
+
+//How a numeric cell is shown, taken from its number format
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+enum NumberFormat{
+    General,
+    Decimals(usize),
+    //Holds the equivalent chrono format
+    Date(String)
+}
+
+//Workbook wide parts every sheet is read against
+struct Workbook{
+    shared_strings: Vec<String>,
+    //Number format of each cell style, indexed by the s attribute of a cell
+    styles: Vec<NumberFormat>,
+    date1904: bool
+}
+
+pub fn read_xlsx(input: &[u8]) -> Result<Vec<(String, DataFrame)>, XlsxError> {
+    /*
+    Reads every sheet of an xlsx workbook, in workbook order, as a named DataFrame.
+    Numbers become the narrowest of I32, I64, F32 or F64 holding them (with the decimal places of their number format), numbers formatted as dates become
+    DATE, TIME or DATETIME and formulas are read through the formula parser. A formula using something this crate does not support
+    keeps the value the spreadsheet last calculated for it. References to other sheets are only solved once the
+    sheets are in a Workbook, see read_workbook.
+     */
+    let mut archive = ZipArchive::new(Cursor::new(input))?;
+
+    let workbook_xml = part(&mut archive, "xl/workbook.xml")?
+        .ok_or_else(|| XlsxError::MissingPart("xl/workbook.xml".to_owned()))?;
+    let relations = relationships(&part(&mut archive, "xl/_rels/workbook.xml.rels")?.unwrap_or_default())?;
+
+    let mut sheets = vec![];
+    let mut date1904 = false;
+    let mut reader = Reader::from_str(&workbook_xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"workbookPr" => date1904 = matches!(attributes(&e)?.get("date1904").map(String::as_str), Some("1" | "true")),
+                b"sheet" => {
+                    let attributes = attributes(&e)?;
+                    let name = attributes.get("name").cloned().unwrap_or_default();
+                    let target = attributes.get("id").and_then(|id| relations.get(id)).cloned()
+                        .ok_or_else(|| XlsxError::MissingPart(format!("the worksheet for sheet '{}'", name)))?;
+                    sheets.push((name, target));
+                },
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let workbook = Workbook{
+        shared_strings: shared_strings(&part(&mut archive, "xl/sharedStrings.xml")?.unwrap_or_default())?,
+        styles: styles(&part(&mut archive, "xl/styles.xml")?.unwrap_or_default())?,
+        date1904
+    };
+
+    let mut dataframes = vec![];
+    for (name, target) in sheets {
+        let xml = part(&mut archive, &target)?.ok_or(XlsxError::MissingPart(target))?;
+        dataframes.push((name, DataFrame::from_values(worksheet(&xml, &workbook)?)));
+    }
+    Ok(dataframes)
+}
+
+pub fn read_workbook(input: &[u8]) -> Result<crate::workbook::Workbook, XlsxError> {
+    //Reads every sheet of an xlsx workbook into a Workbook, so formulas can reference the other sheets
+    crate::workbook::Workbook::from_sheets(read_xlsx(input)?).map_err(|error| match error {
+        WorkbookError::InvalidSheetName(name) | WorkbookError::DuplicateSheetName(name) => XlsxError::InvalidSheetName(name),
+        other => XlsxError::Xml(other.to_string())
+    })
+}
+
+pub fn write_xlsx(sheets: &[(&str, &DataFrame)]) -> Result<Vec<u8>, XlsxError> {
+    /*
+    Writes named DataFrames as the sheets of an xlsx workbook. Formulas are written with the value they solved to
+    so the file shows the same values before it is recalculated.
+     */
+    for (i, (name, _)) in sheets.iter().enumerate() {
+        let invalid = !valid_sheet_name(name)
+            || sheets[..i].iter().any(|(other, _)| other.to_lowercase() == name.to_lowercase());
+        if invalid {
+            return Err(XlsxError::InvalidSheetName(name.to_string()))
+        }
+    }
+
+    let mut formats: Vec<String> = vec![];
+    let worksheets: Vec<String> = sheets.iter().map(|(_, df)| write_worksheet(df, &mut formats)).collect();
+
+    let mut content_types = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+        r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+        r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+        r#"<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+        r#"<Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#
+    ));
+    let mut workbook = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" "#,
+        r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#
+    ));
+    let mut relations = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#
+    ));
+    for (i, (name, _)) in sheets.iter().enumerate() {
+        let number = i + 1;
+        content_types.push_str(&format!(
+            r#"<Override PartName="/xl/worksheets/sheet{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+            number
+        ));
+        workbook.push_str(&format!(r#"<sheet name="{}" sheetId="{}" r:id="rId{}"/>"#, escape(*name), number, number));
+        relations.push_str(&format!(
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{}.xml"/>"#,
+            number, number
+        ));
+    }
+    content_types.push_str("</Types>");
+    workbook.push_str("</sheets></workbook>");
+    relations.push_str(&format!(
+        r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>"#,
+        sheets.len() + 1
+    ));
+    relations.push_str("</Relationships>");
+
+    let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut add = |name: &str, content: &str| -> Result<(), XlsxError> {
+        archive.start_file(name, options)?;
+        archive.write_all(content.as_bytes())?;
+        Ok(())
+    };
+
+    add("[Content_Types].xml", &content_types)?;
+    add("_rels/.rels", concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>"#,
+        r#"</Relationships>"#
+    ))?;
+    add("xl/workbook.xml", &workbook)?;
+    add("xl/_rels/workbook.xml.rels", &relations)?;
+    add("xl/styles.xml", &write_styles(&formats))?;
+    for (i, xml) in worksheets.iter().enumerate() {
+        add(&format!("xl/worksheets/sheet{}.xml", i + 1), xml)?;
+    }
+    Ok(archive.finish()?.into_inner())
+}
+
+fn part(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<Option<String>, XlsxError> {
+    //Reads a file of the archive, None when the archive does not have it
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(error) => return Err(error.into())
+    };
+    let size = file.size();
+    limited(&mut file, name, size, MAX_PART_SIZE).map(Some)
+}
+
+fn limited(file: &mut impl Read, name: &str, size: u64, limit: u64) -> Result<String, XlsxError> {
+    /*
+    Reads a file of the archive up to limit bytes. Both the size the archive declares and the bytes actually read are
+    checked, so a small archive which unpacks to something huge fails instead of using up all memory.
+     */
+    let too_large = || XlsxError::Archive(format!("{} is larger than {} bytes", name, limit));
+    if size > limit {
+        return Err(too_large())
+    }
+    let mut content = String::new();
+    file.take(limit + 1).read_to_string(&mut content)?;
+    if content.len() as u64 > limit {
+        return Err(too_large())
+    }
+    Ok(content)
+}
+
+fn attributes(element: &BytesStart) -> Result<HashMap<String, String>, XlsxError> {
+    //Attributes of an element by name without their namespace prefix, r:id is read as id
+    let mut found = HashMap::new();
+    for attribute in element.attributes() {
+        let attribute = attribute?;
+        let key = String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned();
+        found.insert(key, attribute.unescape_value()?.into_owned());
+    }
+    Ok(found)
+}
+
+fn relationships(xml: &str) -> Result<HashMap<String, String>, XlsxError> {
+    /*
+    Maps each relationship id of the workbook to the path of the part in the archive
+     */
+    let mut relations = HashMap::new();
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Relationship" => {
+                let attributes = attributes(&e)?;
+                if let (Some(id), Some(target)) = (attributes.get("Id"), attributes.get("Target")) {
+                    //Targets are relative to the xl folder unless they start from the root
+                    let path = match target.strip_prefix('/') {
+                        Some(absolute) => absolute.to_owned(),
+                        None => format!("xl/{}", target)
+                    };
+                    relations.insert(id.to_owned(), path);
+                }
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(relations)
+}
+
+fn shared_strings(xml: &str) -> Result<Vec<String>, XlsxError> {
+    /*
+    Text of every shared string, rich text runs are joined and phonetic guides skipped
+     */
+    let mut strings = vec![];
+    let mut reader = Reader::from_str(xml);
+    let (mut in_text, mut in_phonetic) = (false, false);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"si" => strings.push(String::new()),
+                b"t" => in_text = true,
+                b"rPh" => in_phonetic = true,
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"rPh" => in_phonetic = false,
+                _ => {}
+            },
+            Event::Text(e) if in_text && !in_phonetic => {
+                if let Some(last) = strings.last_mut() {
+                    last.push_str(&e.unescape()?);
+                }
+            },
+            Event::CData(e) if in_text && !in_phonetic => {
+                if let Some(last) = strings.last_mut() {
+                    last.push_str(&String::from_utf8_lossy(&e.into_inner()));
+                }
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+fn styles(xml: &str) -> Result<Vec<NumberFormat>, XlsxError> {
+    /*
+    The number format of each cell style
+     */
+    let mut custom: HashMap<usize, String> = HashMap::new();
+    let mut format_ids = vec![];
+    let mut in_cell_styles = false;
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"numFmt" => {
+                    let attributes = attributes(&e)?;
+                    if let (Some(id), Some(code)) = (attributes.get("numFmtId"), attributes.get("formatCode")) {
+                        custom.insert(id.parse().unwrap_or(0), code.to_owned());
+                    }
+                },
+                b"cellXfs" => in_cell_styles = true,
+                b"xf" if in_cell_styles => format_ids.push(attributes(&e)?.get("numFmtId").and_then(|id| id.parse().ok()).unwrap_or(0)),
+                _ => {}
+            },
+            Event::End(e) if e.local_name().as_ref() == b"cellXfs" => in_cell_styles = false,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(format_ids.into_iter()
+        .map(|id| custom.get(&id).map(String::as_str).or(builtin_format(id)).map_or(NumberFormat::General, number_format))
+        .collect())
+}
+
+fn builtin_format(id: usize) -> Option<&'static str> {
+    //Number formats every spreadsheet knows by id without storing them
+    match id {
+        0 => Some("General"),
+        1 => Some("0"),
+        2 => Some("0.00"),
+        3 => Some("#,##0"),
+        4 => Some("#,##0.00"),
+        9 => Some("0%"),
+        10 => Some("0.00%"),
+        11 => Some("0.00E+00"),
+        14 => Some("mm-dd-yy"),
+        15 => Some("d-mmm-yy"),
+        16 => Some("d-mmm"),
+        17 => Some("mmm-yy"),
+        18 => Some("h:mm AM/PM"),
+        19 => Some("h:mm:ss AM/PM"),
+        20 => Some("h:mm"),
+        21 => Some("h:mm:ss"),
+        22 => Some("m/d/yy h:mm"),
+        37 | 38 => Some("#,##0 ;(#,##0)"),
+        39 | 40 => Some("#,##0.00;(#,##0.00)"),
+        45 => Some("mm:ss"),
+        46 => Some("[h]:mm:ss"),
+        47 => Some("mmss.0"),
+        48 => Some("##0.0E+0"),
+        _ => None
+    }
+}
+
+fn number_format(code: &str) -> NumberFormat {
+    /*
+    Works out whether a format code shows a date or how many decimal places it shows a number with.
+    Only the first section of the code, used for positive numbers, is looked at.
+     */
+    let section = code.split(';').next().unwrap_or("");
+    //Colours and conditions in brackets such as [Red] do not change what a General number shows
+    let unbracketed: String = section.split('[').map(|piece| piece.split_once(']').map_or(piece, |(_, after)| after)).collect();
+    if unbracketed.trim().eq_ignore_ascii_case("general") {
+        return NumberFormat::General
+    }
+
+    let tokens = format_tokens(section);
+    if tokens.iter().any(|token| matches!(token, FormatToken::Part(_, _) | FormatToken::AmPm)) {
+        return NumberFormat::Date(chrono_format(&tokens))
+    }
+
+    let decimals = section.split_once('.')
+        .map_or(0, |(_, after)| after.chars().take_while(|c| matches!(c, '0' | '#' | '?')).count());
+    NumberFormat::Decimals(decimals)
+}
+
+//A piece of a spreadsheet date format code
+#[derive(PartialEq)]
+#[derive(Debug)]
+enum FormatToken{
+    //A run of the same date letter, e.g. yyyy is ('y', 4)
+    Part(char, usize),
+    AmPm,
+    Literal(char)
+}
+
+fn format_tokens(section: &str) -> Vec<FormatToken> {
+    let chars: Vec<char> = section.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let rest: String = chars[i..].iter().collect::<String>().to_uppercase();
+        if rest.starts_with("AM/PM") || rest.starts_with("A/P") {
+            i += if rest.starts_with("AM/PM") {5} else {3};
+            tokens.push(FormatToken::AmPm);
+            continue
+        }
+
+        match c.to_ascii_lowercase() {
+            '"' => {
+                i += 1;
+                while let Some(literal) = chars.get(i).filter(|literal| **literal != '"') {
+                    tokens.push(FormatToken::Literal(*literal));
+                    i += 1;
+                }
+                i += 1;
+            },
+            '\\' => {
+                tokens.extend(chars.get(i + 1).map(|literal| FormatToken::Literal(*literal)));
+                i += 2;
+            },
+            //Padding and fill characters take the following character with them
+            '_' | '*' => i += 2,
+            '[' => {
+                //Elapsed time such as [h] reads as the plain part, colours and locales are skipped
+                let end = chars[i..].iter().position(|c| *c == ']').map_or(chars.len(), |end| i + end);
+                let inner: String = chars[i + 1..end].iter().collect::<String>().to_lowercase();
+                if let Some(first) = inner.chars().next().filter(|first| "hms".contains(*first) && inner.chars().all(|c| c == *first)) {
+                    tokens.push(FormatToken::Part(first, inner.len()));
+                }
+                i = end + 1;
+            },
+            //E+ and E- are the exponent of scientific notation rather than a year
+            'e' if matches!(chars.get(i + 1), Some('+' | '-')) => {
+                tokens.push(FormatToken::Literal(c));
+                i += 1;
+            },
+            letter @ ('y' | 'm' | 'd' | 'h' | 's' | 'e') => {
+                let count = chars[i..].iter().take_while(|c| c.to_ascii_lowercase() == letter).count();
+                tokens.push(FormatToken::Part(if letter == 'e' {'y'} else {letter}, count));
+                i += count;
+            },
+            //Fractions of a second are dropped, a DATETIME keeps whole seconds
+            '.' if matches!(tokens.last(), Some(FormatToken::Part('s', _))) => {
+                i += 1;
+                while chars.get(i) == Some(&'0') {
+                    i += 1;
+                }
+            },
+            _ => {
+                tokens.push(FormatToken::Literal(c));
+                i += 1;
+            }
+        }
+    }
+    tokens
+}
+
+fn chrono_format(tokens: &[FormatToken]) -> String {
+    /*
+    Converts a spreadsheet date format into a chrono format. An m is minutes straight after hours or before seconds,
+    otherwise it is the month.
+     */
+    let twelve_hour = tokens.contains(&FormatToken::AmPm);
+    let parts: Vec<(usize, char)> = tokens.iter().enumerate()
+        .filter_map(|(i, token)| match token {FormatToken::Part(letter, _) => Some((i, *letter)), _ => None})
+        .collect();
+
+    let mut format = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            FormatToken::Literal('%') => format.push_str("%%"),
+            FormatToken::Literal(c) => format.push(*c),
+            FormatToken::AmPm => format.push_str("%p"),
+            FormatToken::Part(letter, count) => {
+                let position = parts.iter().position(|(index, _)| *index == i).unwrap_or(0);
+                let minutes = *letter == 'm' && (
+                    (position > 0 && parts[position - 1].1 == 'h') || parts.get(position + 1).is_some_and(|next| next.1 == 's')
+                );
+                format.push_str(match (letter, count, minutes) {
+                    ('y', 1..=2, _) => "%y",
+                    ('y', _, _) => "%Y",
+                    ('m', 1, true) => "%-M",
+                    ('m', _, true) => "%M",
+                    ('m', 1, _) => "%-m",
+                    ('m', 2, _) => "%m",
+                    ('m', 3, _) | ('m', 5.., _) => "%b",
+                    ('m', _, _) => "%B",
+                    ('d', 1, _) => "%-d",
+                    ('d', 2, _) => "%d",
+                    ('d', 3, _) => "%a",
+                    ('d', _, _) => "%A",
+                    ('h', 1, _) => if twelve_hour {"%-I"} else {"%-H"},
+                    ('h', _, _) => if twelve_hour {"%I"} else {"%H"},
+                    ('s', 1, _) => "%-S",
+                    _ => "%S"
+                });
+            }
+        }
+    }
+    format
+}
+
+fn excel_format(format: &str) -> String {
+    /*
+    Converts a chrono format into a spreadsheet date format, the reverse of chrono_format
+     */
+    let mut code = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            //Characters which mean something in a format code are escaped
+            if !matches!(c, ' ' | '-' | '/' | ':' | ',') {
+                code.push('\\');
+            }
+            code.push(c);
+            continue
+        }
+
+        let mut specifier = chars.next();
+        let padded = specifier != Some('-');
+        if !padded {
+            specifier = chars.next();
+        }
+        //Offsets and fractions of a second, %:z and %.f, have no equivalent and are left out
+        if matches!(specifier, Some(':') | Some('.')) {
+            chars.next();
+            continue
+        }
+        code.push_str(match (specifier, padded) {
+            (Some('Y'), _) => "yyyy",
+            (Some('y'), _) => "yy",
+            (Some('m'), true) => "mm",
+            (Some('m'), false) => "m",
+            (Some('d'), true) => "dd",
+            (Some('d'), false) | (Some('e'), _) => "d",
+            (Some('H'), true) | (Some('I'), true) => "hh",
+            (Some('H'), false) | (Some('I'), false) => "h",
+            (Some('M'), true) => "mm",
+            (Some('M'), false) => "m",
+            (Some('S'), true) => "ss",
+            (Some('S'), false) => "s",
+            (Some('B'), _) => "mmmm",
+            (Some('b'), _) | (Some('h'), _) => "mmm",
+            (Some('A'), _) => "dddd",
+            (Some('a'), _) => "ddd",
+            (Some('p'), _) | (Some('P'), _) => "AM/PM",
+            (Some('F'), _) => "yyyy-mm-dd",
+            (Some('T'), _) => "hh:mm:ss",
+            (Some('R'), _) => "hh:mm",
+            (Some('D'), _) => "mm/dd/yy",
+            (Some('%'), _) => "\\%",
+            _ => ""
+        });
+    }
+    code
+}
+
+fn date_value(datetime: NaiveDateTime, serial: f64, format: &str) -> Value {
+    /*
+    A format showing only a date reads as a DATE and one showing only a time as a TIME, as long as the serial number
+    has nothing the format does not show. Anything else is a DATETIME.
+     */
+    let mut chars = format.chars();
+    let mut specifiers = vec![];
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            specifiers.extend(chars.by_ref().find(|c| !matches!(c, '-' | ':' | '.')));
+        }
+    }
+    let shows = |letters: &str| specifiers.iter().any(|specifier| letters.contains(*specifier));
+    match (shows("YymdbBhaAeFD"), shows("HIMSpPTR")) {
+        (true, false) if serial.fract() == 0.0 => Value::DATE(datetime.date(), format.to_owned()),
+        (false, true) if (0.0..1.0).contains(&serial) => Value::TIME(datetime.time(), format.to_owned()),
+        _ => Value::DATETIME(datetime, format.to_owned())
+    }
+}
+
+fn date_base(date1904: bool) -> NaiveDateTime {
+    //Serial day 0. Starting at 1899-12-30 rather than 1900-01-01 absorbs the 1900 leap year bug for dates after March 1900
+    let (year, month, day) = if date1904 {(1904, 1, 1)} else {(1899, 12, 30)};
+    NaiveDate::from_ymd_opt(year, month, day).and_then(|date| date.and_hms_opt(0, 0, 0)).unwrap()
+}
+
+fn serial_to_datetime(serial: f64, date1904: bool) -> Option<NaiveDateTime> {
+    let seconds = (serial * 86_400.0).round() as i64;
+    date_base(date1904).checked_add_signed(TimeDelta::try_seconds(seconds)?)
+}
+
+fn datetime_to_serial(datetime: &NaiveDateTime) -> f64 {
+    (*datetime - date_base(false)).num_seconds() as f64 / 86_400.0
+}
+
+//The parts of a <c> element needed to work out its value
+#[derive(Default)]
+struct CellXml{
+    coordinate: Option<Coordinate>,
+    kind: String,
+    style: usize,
+    value: Option<String>,
+    formula: Option<String>,
+    inline: String
+}
+
+fn worksheet(xml: &str, workbook: &Workbook) -> Result<Array2<Value>, XlsxError> {
+    /*
+    Reads the cells of a worksheet, the data is sized to the furthest cell with a value. A value further out than
+    MAX_CELLS allows is an invalid cell.
+     */
+    let mut cells: Vec<(Coordinate, Value)> = vec![];
+    let (mut rows, mut columns) = (0, 0);
+    let mut cell = CellXml::default();
+    //Shared formulas are written once and reused by the following cells, moved relative to where they are used
+    let mut shared: HashMap<String, (String, Coordinate)> = HashMap::new();
+    let mut shared_id: Option<String> = None;
+    let (mut row, mut column) = (0, 0);
+    let mut target: Option<&[u8]> = None;
+
+    let mut reader = Reader::from_str(xml);
+    loop {
+        let event = reader.read_event()?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let empty = matches!(event, Event::Empty(_));
+                match e.local_name().as_ref() {
+                    b"row" => {
+                        if let Some(r) = attributes(e)?.get("r") {
+                            row = r.parse::<usize>().map_err(|_| XlsxError::InvalidCell(format!("row {}", r)))?.saturating_sub(1);
+                        }
+                        column = 0;
+                    },
+                    b"c" => {
+                        let attributes = attributes(e)?;
+                        let coordinate = match attributes.get("r") {
+                            Some(r) => excel_coordinate(r).ok_or_else(|| XlsxError::InvalidCell(r.to_owned()))?,
+                            None => Coordinate{row, column}
+                        };
+                        column = coordinate.column + 1;
+                        cell = CellXml{
+                            coordinate: Some(coordinate),
+                            kind: attributes.get("t").cloned().unwrap_or_default(),
+                            style: attributes.get("s").and_then(|s| s.parse().ok()).unwrap_or(0),
+                            ..CellXml::default()
+                        };
+                    },
+                    b"v" => target = Some(b"v"),
+                    b"t" => target = Some(b"t"),
+                    b"f" => {
+                        let attributes = attributes(e)?;
+                        shared_id = attributes.get("si").filter(|_| attributes.get("t").is_some_and(|t| t == "shared")).cloned();
+                        cell.formula = Some(String::new());
+                        target = Some(b"f");
+                        if empty {
+                            resolve_shared(&mut cell, &mut shared, shared_id.take());
+                        }
+                    },
+                    _ => {}
+                }
+            },
+            Event::Text(e) => {
+                let text = e.unescape()?;
+                match target {
+                    Some(b"v") => cell.value.get_or_insert_with(String::new).push_str(&text),
+                    Some(b"t") => cell.inline.push_str(&text),
+                    Some(b"f") => cell.formula.get_or_insert_with(String::new).push_str(&text),
+                    _ => {}
+                }
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"v" | b"t" => target = None,
+                b"f" => {
+                    target = None;
+                    resolve_shared(&mut cell, &mut shared, shared_id.take());
+                },
+                b"c" => {
+                    let finished = std::mem::take(&mut cell);
+                    if let Some(coordinate) = finished.coordinate {
+                        let value = cell_value(&finished, coordinate, workbook)?;
+                        if value != Value::NULL() {
+                            rows = usize::max(rows, coordinate.row + 1);
+                            columns = usize::max(columns, coordinate.column + 1);
+                            if rows.checked_mul(columns).is_none_or(|count| count > MAX_CELLS) {
+                                return Err(XlsxError::InvalidCell(coordinate.to_string()))
+                            }
+                            cells.push((coordinate, value));
+                        }
+                    }
+                },
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let mut data = Array2::<Value>::default((rows, columns));
+    for (coordinate, value) in cells {
+        data[(coordinate.row, coordinate.column)] = value;
+    }
+    Ok(data)
+}
+
+fn resolve_shared(cell: &mut CellXml, shared: &mut HashMap<String, (String, Coordinate)>, id: Option<String>) {
+    /*
+    The first cell of a shared formula holds its text, the others only its id and get the text moved to their position
+     */
+    let (Some(id), Some(coordinate)) = (id, cell.coordinate) else {return};
+    match cell.formula.as_deref() {
+        Some(text) if !text.is_empty() => {
+            shared.insert(id, (text.to_owned(), coordinate));
+        },
+        _ => if let Some((text, origin)) = shared.get(&id) {
+            let offset = (coordinate.row as i64 - origin.row as i64, coordinate.column as i64 - origin.column as i64);
+            cell.formula = Some(move_formula(text, offset));
+        }
+    }
+}
+
+fn cell_value(cell: &CellXml, coordinate: Coordinate, workbook: &Workbook) -> Result<Value, XlsxError> {
+    if let Some(formula) = cell.formula.as_ref().filter(|formula| !formula.is_empty()) {
+        if let Ok(value) = parser::parse(&native_formula(formula)) {
+            return Ok(value)
+        }
+    }
+
+    let text = cell.value.as_deref().unwrap_or("");
+    let value = match cell.kind.as_str() {
+        "inlineStr" => Value::STRING(cell.inline.to_owned()),
+        _ if cell.value.is_none() => Value::NULL(),
+        "s" => text.trim().parse::<usize>().ok()
+            .and_then(|index| workbook.shared_strings.get(index))
+            .map(|shared| Value::STRING(shared.to_owned()))
+            .ok_or_else(|| XlsxError::InvalidCell(coordinate.to_string()))?,
+        "str" => Value::STRING(text.to_owned()),
+        "b" => Value::BOOLEAN(text.trim() == "1"),
+        //Spreadsheet only errors such as #NULL! have no equivalent, they are shown as #VALUE!
+        "e" => Value::ERROR(ErrorKind::from_code(text.trim()).unwrap_or(ErrorKind::VALUE)),
+        "d" => NaiveDateTime::parse_from_str(text.trim(), "%Y-%m-%dT%H:%M:%S")
+            .map(|datetime| Value::DATETIME(datetime, DATETIME_FORMAT.to_owned()))
+            .map_err(|_| XlsxError::InvalidCell(coordinate.to_string()))?,
+        _ => {
+            let number = text.trim().parse::<f64>().map_err(|_| XlsxError::InvalidCell(coordinate.to_string()))?;
+            match workbook.styles.get(cell.style).unwrap_or(&NumberFormat::General) {
+                NumberFormat::Date(format) => serial_to_datetime(number, workbook.date1904)
+                    .map(|datetime| date_value(datetime, number, format))
+                    .ok_or_else(|| XlsxError::InvalidCell(coordinate.to_string()))?,
+                NumberFormat::Decimals(0) if number.fract() == 0.0 && number.abs() < i64::MAX as f64 => Value::integer(number as i64),
+                NumberFormat::Decimals(decimals) => Value::float(number, *decimals),
+                NumberFormat::General => match Value::from_number_str(text.trim()) {
+                    //Binary floating point noise such as 0.30000000000000004 is trimmed
+                    Some(Value::F32(val, precision)) => Value::F32(val, precision.min(fitted_precision(val as f64))),
+                    Some(Value::F64(val, precision)) => Value::float(val, precision.min(fitted_precision(val))),
+                    Some(value) => value,
+                    None => return Err(XlsxError::InvalidCell(coordinate.to_string()))
+                }
+            }
+        }
+    };
+    Ok(value)
+}
+
+fn excel_coordinate(reference: &str) -> Option<Coordinate> {
+    //A1 style reference of a spreadsheet file, rows count from 1
+    let split = reference.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = reference.split_at(split);
+    Some(Coordinate{row: digits.parse::<usize>().ok()?.checked_sub(1)?, column: Coordinate::column_number(letters)?})
+}
+
+fn native_formula(formula: &str) -> String {
+    /*
+    Converts the formula text stored in a spreadsheet file into the syntax this crate parses,
+    e.g. SUM(A1:B2)+$C3 becomes =SUM([A0:B1])+[$C2] and 'My Sheet'!A1 becomes =['My Sheet'!A0]
+     */
+    let native_row = |row: &str| row.parse::<usize>().ok().and_then(|row| row.checked_sub(1));
+
+    let reference = |word: &str| -> Option<String> {
+        let captures = REFERENCE.captures(word)?;
+        Some(format!("{}{}{}{}", &captures[1], captures[2].to_uppercase(), &captures[3], native_row(&captures[4])?))
+    };
+    let range = |start: &str, end: &str| -> Option<String> {
+        if let (Some(start), Some(end)) = (reference(start), reference(end)) {
+            return Some(format!("{}:{}", start, end))
+        }
+        if let (Some(start), Some(end)) = (COLUMN.captures(start), COLUMN.captures(end)) {
+            return Some(format!("{}{}:{}{}", &start[1], start[2].to_uppercase(), &end[1], end[2].to_uppercase()))
+        }
+        let (start, end) = (ROW.captures(start)?, ROW.captures(end)?);
+        Some(format!("{}{}:{}{}", &start[1], native_row(&start[2])?, &end[1], native_row(&end[2])?))
+    };
+
+    let mut native = String::from("=");
+    let mut sheet: Option<(String, String)> = None;
+    for piece in scan_formula(formula) {
+        let (word, end, next) = match piece {
+            FormulaPiece::Word(word, end, next) => (word, end, next),
+            FormulaPiece::Sheet(name, text) => {
+                sheet = Some((name, text));
+                continue
+            },
+            FormulaPiece::Other(text) => {
+                native.push_str(&sheet.take().map(|(_, written)| written).unwrap_or_default());
+                native.push_str(&text);
+                continue
+            }
+        };
+        let end = end.as_deref();
+
+        //A reference on another sheet has the sheet inside its brackets, anything else keeps the sheet as written
+        let (prefix, written) = match sheet.take() {
+            Some((name, written)) => (format!("{}!", sheet_name(&name)), written),
+            None => (String::new(), String::new())
+        };
+        let converted = match end {
+            _ if next == Some('(') => None,
+            Some(end) => range(&word, end),
+            None => reference(&word)
+        };
+        match converted {
+            Some(converted) => native.push_str(&format!("[{}{}]", prefix, converted)),
+            None if next == Some('(') => {
+                //Newer functions are stored with a prefix, CEILING is called CEIL here
+                let name = word.trim_start_matches("_xlfn.");
+                native.push_str(&written);
+                native.push_str(if name.eq_ignore_ascii_case("CEILING") {"CEIL"} else {name})
+            },
+            None => {
+                native.push_str(&written);
+                native.push_str(&word);
+                if let Some(end) = end {
+                    native.push_str(&format!(":{}", end));
+                }
+            }
+        }
+    }
+    native
+}
+
+fn move_formula(formula: &str, offset: (i64, i64)) -> String {
+    /*
+    Moves every relative reference of a spreadsheet formula by (rows, columns), parts anchored with $ stay put.
+    A reference moved off the sheet becomes #REF!
+     */
+    let moved = |word: &str| -> Option<Option<String>> {
+        let captures = REFERENCE.captures(word)?;
+        let column = Coordinate::column_number(&captures[2])? as i64 + if captures[1].is_empty() {offset.1} else {0};
+        let row = captures[4].parse::<i64>().ok()? + if captures[3].is_empty() {offset.0} else {0};
+        if column < 0 || row < 1 {
+            return Some(None)
+        }
+        Some(Some(format!("{}{}{}{}", &captures[1], Coordinate::column_name(column as usize), &captures[3], row)))
+    };
+
+    let mut output = String::new();
+    for piece in scan_formula(formula) {
+        let (word, end) = match &piece {
+            FormulaPiece::Word(word, _, Some('(')) | FormulaPiece::Sheet(_, word) | FormulaPiece::Other(word) => {
+                output.push_str(word);
+                continue
+            },
+            FormulaPiece::Word(word, end, _) => (word.as_str(), end.as_deref())
+        };
+        let moved_word = moved(word);
+        let moved_end = end.map(moved);
+        match (moved_word, moved_end) {
+            (Some(None), _) | (_, Some(Some(None))) => output.push_str("#REF!"),
+            (Some(Some(start)), Some(Some(Some(end)))) => output.push_str(&format!("{}:{}", start, end)),
+            (Some(Some(start)), None) => output.push_str(&start),
+            _ => {
+                output.push_str(word);
+                if let Some(end) = end {
+                    output.push_str(&format!(":{}", end));
+                }
+            }
+        }
+    }
+    output
+}
+
+//A piece of a spreadsheet formula
+enum FormulaPiece{
+    //A name, reference or number with the end of a range joined on, and the character following it
+    Word(String, Option<String>, Option<char>),
+    //The sheet a reference is on, unquoted, and as it was written including the '!'
+    Sheet(String, String),
+    //Operators, brackets and text in quotes
+    Other(String)
+}
+
+fn scan_formula(formula: &str) -> Vec<FormulaPiece> {
+    let mut pieces = vec![];
+    let chars: Vec<char> = formula.chars().collect();
+    let is_word = |c: char| c.is_ascii_alphanumeric() || matches!(c, '$' | '_' | '.');
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        if chars[i] == '"' {
+            i += 1;
+            while i < chars.len() && !(chars[i] == '"' && chars.get(i + 1) != Some(&'"')) {
+                i += if chars[i] == '"' {2} else {1};
+            }
+            i = (i + 1).min(chars.len());
+            pieces.push(FormulaPiece::Other(chars[start..i].iter().collect()));
+        } else if chars[i] == '\'' {
+            //Quoted sheet names, quotes inside the name are doubled
+            i += 1;
+            while i < chars.len() && !(chars[i] == '\'' && chars.get(i + 1) != Some(&'\'')) {
+                i += if chars[i] == '\'' {2} else {1};
+            }
+            i = (i + 1).min(chars.len());
+            let quoted: String = chars[start..i].iter().collect();
+            if chars.get(i) == Some(&'!') {
+                i += 1;
+                let name = quoted[1..quoted.len() - 1].replace("''", "'");
+                pieces.push(FormulaPiece::Sheet(name, chars[start..i].iter().collect()));
+            } else {
+                pieces.push(FormulaPiece::Other(quoted));
+            }
+        } else if is_word(chars[i]) {
+            while i < chars.len() && is_word(chars[i]) {
+                i += 1;
+            }
+            let first: String = chars[start..i].iter().collect();
+            if chars.get(i) == Some(&'!') {
+                i += 1;
+                pieces.push(FormulaPiece::Sheet(first, chars[start..i].iter().collect()));
+                continue
+            }
+            let mut end = None;
+            if chars.get(i) == Some(&':') && chars.get(i + 1).is_some_and(|c| is_word(*c)) {
+                let end_start = i + 1;
+                i += 1;
+                while i < chars.len() && is_word(chars[i]) {
+                    i += 1;
+                }
+                end = Some(chars[end_start..i].iter().collect::<String>());
+            }
+            pieces.push(FormulaPiece::Word(first, end, chars.get(i).copied()));
+        } else {
+            i += 1;
+            pieces.push(FormulaPiece::Other(chars[start..i].iter().collect()));
+        }
+    }
+    pieces
+}
+
+fn write_worksheet(df: &DataFrame, formats: &mut Vec<String>) -> String {
+    /*
+    Writes the cells of a DataFrame as a worksheet, number formats used are added to formats
+     */
+    let mut xml = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#
+    ));
+    for (row, cells) in df.data.rows().into_iter().enumerate() {
+        let mut written = String::new();
+        for (column, raw) in cells.iter().enumerate() {
+            let reference = format!("{}{}", Coordinate::column_name(column), row + 1);
+            let cell = match raw {
+                Value::NULL() => continue,
+                Value::FUNCTION(_, _) | Value::CELL_REFERENCE(_, _) | Value::RANGE(_, _) | Value::SHEET_REFERENCE(_, _) => {
+                    let (kind, style, value) = cell_content(&df.solved[(row, column)], formats);
+                    format!(r#"<c r="{}"{}{}><f>{}</f>{}</c>"#, reference, style, kind, escape(print_excel(raw)), value)
+                },
+                Value::STRING(text) => format!(
+                    r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#, reference, escape(text.as_str())
+                ),
+                constant => {
+                    let (kind, style, value) = cell_content(constant, formats);
+                    format!(r#"<c r="{}"{}{}>{}</c>"#, reference, style, kind, value)
+                }
+            };
+            written.push_str(&cell);
+        }
+        if !written.is_empty() {
+            xml.push_str(&format!(r#"<row r="{}">{}</row>"#, row + 1, written));
+        }
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+fn cell_content(value: &Value, formats: &mut Vec<String>) -> (String, String, String) {
+    /*
+    The type attribute, style attribute and <v> element of a cell holding value
+     */
+    let mut style = |code: String| -> String {
+        let index = formats.iter().position(|format| *format == code).unwrap_or_else(|| {
+            formats.push(code);
+            formats.len() - 1
+        });
+        //Style 0 is the default, custom styles follow in the order their formats were added
+        format!(r#" s="{}""#, index + 1)
+    };
+
+    match value {
+        Value::I32(val) => (String::new(), String::new(), format!("<v>{}</v>", val)),
+        Value::I64(val) => (String::new(), String::new(), format!("<v>{}</v>", val)),
+        Value::F32(val, precision) => (String::new(), style(decimals_code(*precision)), format!("<v>{}</v>", val)),
+        Value::F64(val, precision) => (String::new(), style(decimals_code(*precision)), format!("<v>{}</v>", val)),
+        //Spreadsheets store every number as a float, a DECIMAL keeps its places through the number format
+        Value::DECIMAL(_, places) => (String::new(), style(decimals_code(*places)), format!("<v>{}</v>", value.display())),
+        Value::DATE(date, format) =>
+            (String::new(), style(excel_format(format)), format!("<v>{}</v>", datetime_to_serial(&date.and_time(NaiveTime::MIN)))),
+        //A time alone is the fraction of a day
+        Value::TIME(time, format) =>
+            (String::new(), style(excel_format(format)), format!("<v>{}</v>", time.num_seconds_from_midnight() as f64 / 86_400.0)),
+        Value::DATETIME(datetime, format) => (String::new(), style(excel_format(format)), format!("<v>{}</v>", datetime_to_serial(datetime))),
+        //Spreadsheets have no time zones, a TIMESTAMP is written as the time it shows
+        Value::TIMESTAMP(timestamp, format) =>
+            (String::new(), style(excel_format(format)), format!("<v>{}</v>", datetime_to_serial(&timestamp.naive_local()))),
+        Value::STRING(text) => (r#" t="str""#.to_owned(), String::new(), format!("<v>{}</v>", escape(text.as_str()))),
+        Value::BOOLEAN(val) => (r#" t="b""#.to_owned(), String::new(), format!("<v>{}</v>", *val as i32)),
+        Value::ERROR(kind) => (r#" t="e""#.to_owned(), String::new(), format!("<v>{}</v>", escape(kind.to_string().as_str()))),
+        _ => (String::new(), String::new(), String::new())
+    }
+}
+
+fn decimals_code(precision: usize) -> String {
+    if precision == 0 {"0".to_owned()} else {format!("0.{}", "0".repeat(precision))}
+}
+
+fn write_styles(formats: &[String]) -> String {
+    //A minimal stylesheet with one cell style per number format
+    let mut xml = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#
+    ));
+    if !formats.is_empty() {
+        xml.push_str(&format!(r#"<numFmts count="{}">"#, formats.len()));
+        for (i, code) in formats.iter().enumerate() {
+            xml.push_str(&format!(r#"<numFmt numFmtId="{}" formatCode="{}"/>"#, FIRST_CUSTOM_FORMAT + i, escape(code.as_str())));
+        }
+        xml.push_str("</numFmts>");
+    }
+    xml.push_str(concat!(
+        r#"<fonts count="1"><font><sz val="11"/><name val="Calibri"/></font></fonts>"#,
+        r#"<fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills>"#,
+        r#"<borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders>"#,
+        r#"<cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>"#
+    ));
+    xml.push_str(&format!(r#"<cellXfs count="{}"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/>"#, formats.len() + 1));
+    for i in 0..formats.len() {
+        xml.push_str(&format!(
+            r#"<xf numFmtId="{}" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>"#,
+            FIRST_CUSTOM_FORMAT + i
+        ));
+    }
+    xml.push_str(r#"</cellXfs><cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles></styleSheet>"#);
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+    use chrono::NaiveDateTime;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+    use crate::coordinate::Coordinate;
+    use crate::error::ErrorKind;
+    use crate::io::xlsx::{builtin_format, excel_format, limited, move_formula, native_formula, number_format, read_workbook, read_xlsx, write_xlsx, NumberFormat, XlsxError};
+    use crate::value::Value;
+    use crate::workbook::Workbook;
+    use crate::DataFrame;
+
+    fn strings(records: &[&[&str]]) -> Vec<Vec<String>> {
+        records.iter().map(|record| record.iter().map(|field| field.to_string()).collect()).collect()
+    }
+
+    fn archive(parts: &[(&str, &str)]) -> Vec<u8> {
+        let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in parts {
+            archive.start_file(*name, SimpleFileOptions::default()).unwrap();
+            archive.write_all(content.as_bytes()).unwrap();
+        }
+        archive.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_number_formats(){
+        assert_eq!(NumberFormat::General, number_format("General"));
+        assert_eq!(NumberFormat::Decimals(2), number_format("#,##0.00;[Red]-#,##0.00"));
+        assert_eq!(NumberFormat::Decimals(0), number_format("0"));
+        assert_eq!(NumberFormat::Date("%d/%m/%Y %H:%M".to_owned()), number_format("dd/mm/yyyy hh:mm"));
+        assert_eq!(NumberFormat::Date("%-I:%M %p".to_owned()), number_format("[$-409]h:mm AM/PM;@"));
+        assert_eq!(NumberFormat::Date("%d %b at %H".to_owned()), number_format("dd mmm \"at\" hh"));
+        assert_eq!(NumberFormat::Decimals(2), number_format(builtin_format(11).unwrap()));
+        assert_eq!(NumberFormat::Decimals(1), number_format(builtin_format(48).unwrap()));
+        assert_eq!(NumberFormat::General, number_format("[Red]General"));
+        //An e on its own is still the year of a date
+        assert!(matches!(number_format("e/m/d"), NumberFormat::Date(_)));
+
+        //Writing a chrono format and reading it back gives the same format
+        for format in ["%Y%m%d %H%M%S", "%d/%m/%Y", "%-m/%-d/%y %-I:%M %p", "%A %d %B %Y"] {
+            assert_eq!(NumberFormat::Date(format.to_owned()), number_format(&excel_format(format)))
+        }
+        assert_eq!("yyyy-mm-dd\\Thh:mm:ss", excel_format("%Y-%m-%dT%H:%M:%S%.f%:z"));
+    }
+
+    #[test]
+    fn test_formulas(){
+        assert_eq!("=SUM([A0:B1])+[$C$2]*[D:E]-[3:4]", native_formula("SUM(A1:B2)+$C$3*D:E-4:5"));
+        assert_eq!("=[A$0]+SUM([$A:B])-SUM([$3:$4])", native_formula("A$1+SUM($A:B)-SUM($4:$5)"));
+        assert_eq!("=CEIL([A0],2)&\"B1\"", native_formula("_xlfn.CEILING(a1,2)&\"B1\""));
+        assert_eq!("B3*$A$1+SUM(C$2:$D4)&\"A1\"", move_formula("A2*$A$1+SUM(B$2:$D3)&\"A1\"", (1, 1)));
+        assert_eq!("#REF!+1", move_formula("A1+1", (-1, 0)));
+        assert_eq!("=[Sales!A0]+SUM(['My ''Q1'''!B:B])+Other!Name", native_formula("Sales!A1+SUM('My ''Q1'''!B:B)+Other!Name"));
+        assert_eq!("Sales!B2+'Q 1'!$A$1", move_formula("Sales!A1+'Q 1'!$A$1", (1, 1)));
+    }
+
+    #[test]
+    fn test_round_trip(){
+        let first = DataFrame::new(strings(&[
+            &["1", "2.50", "text, \"quoted\" <tag>"],
+            &["=SUM([A0:B0])*2", "20230425 130000", "=1/0"],
+            &["TRUE", "'12", "=[C0]&\"!\""],
+            &["3000000000", "1234567.10", "=[A3]*4"],
+            &["18/04/2021", "21:03", "04/18/2021 21:03:28"]
+        ]));
+        let second = DataFrame::new(strings(&[&["=CEIL(-[A1], 2)"], &["-3"], &["=[A1]<0"]]));
+
+        let output = write_xlsx(&[("Data", &first), ("Other & more", &second)]).unwrap();
+        let sheets = read_xlsx(&output).unwrap();
+
+        assert_eq!(vec!["Data", "Other & more"], sheets.iter().map(|(name, _)| name.as_str()).collect::<Vec<&str>>());
+        for (original, (_, read)) in [&first, &second].into_iter().zip(&sheets) {
+            assert_eq!(original.dim(), read.dim());
+            for ((row, column), _) in original.data.indexed_iter() {
+                let coordinate = Coordinate{row, column};
+                assert_eq!(original.raw(coordinate), read.raw(coordinate));
+                assert_eq!(original.value(coordinate), read.value(coordinate));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sheet_references(){
+        let mut workbook = Workbook::new();
+        workbook.add_sheet("Unit Costs", DataFrame::new(strings(&[&["2", "3"]]))).unwrap();
+        workbook.add_sheet("Totals", DataFrame::new(strings(&[&["=SUM(['Unit Costs'!A0:B0])*[Unit Costs!B0]"]]))).unwrap();
+
+        let output = write_xlsx(&workbook.sheets()).unwrap();
+        let read = read_workbook(&output).unwrap();
+        let total = Coordinate{row: 0, column: 0};
+        assert_eq!(Some(&Value::I32(15)), read.value("Totals", total));
+        assert_eq!(workbook.sheet("Totals").unwrap().raw(total), read.sheet("Totals").unwrap().raw(total));
+    }
+
+    #[test]
+    fn test_read_workbook(){
+        //A workbook as a desktop spreadsheet writes it, with shared strings, shared formulas and styles
+        let parts = [
+            ("xl/workbook.xml", r#"<workbook xmlns:r="r"><workbookPr date1904="false"/><sheets><sheet name="Sales" sheetId="1" r:id="rId1"/></sheets></workbook>"#),
+            ("xl/_rels/workbook.xml.rels", r#"<Relationships><Relationship Id="rId1" Target="/xl/worksheets/sheet1.xml"/></Relationships>"#),
+            ("xl/sharedStrings.xml", r#"<sst><si><t>Name</t></si><si><r><t>Rich </t></r><r><t>text</t></r><rPh><t>x</t></rPh></si><si><t>007</t></si></sst>"#),
+            ("xl/styles.xml", r#"<styleSheet><numFmts><numFmt numFmtId="164" formatCode="dd/mm/yyyy"/></numFmts>
+                <cellXfs><xf numFmtId="0"/><xf numFmtId="164"/><xf numFmtId="2"/></cellXfs></styleSheet>"#),
+            ("xl/worksheets/sheet1.xml", r#"<worksheet><sheetData>
+                <row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c><c r="C1" t="s"><v>2</v></c></row>
+                <row r="2"><c r="A2"><v>3</v></c><c r="B2" s="1"><v>45041</v></c><c r="C2" s="2"><v>1.5</v></c></row>
+                <row r="3"><c r="A3"><v>4</v></c><c r="B3" t="b"><v>1</v></c><c r="C3" t="e"><v>#NULL!</v></c></row>
+                <row r="4"><c r="A4"><f t="shared" ref="A4:A5" si="0">A2*2</f><v>6</v></c><c r="C4"><f>VLOOKUP(A2,A1:B3,2)</f><v>0.30000000000000004</v></c></row>
+                <row r="5"><c r="A5"><f t="shared" si="0"/><v>8</v></c><c r="B5" t="inlineStr"><is><t>inline</t></is></c></row>
+                <row r="7"><c r="D7"><v>1</v></c></row>
+            </sheetData></worksheet>"#)
+        ];
+        let sheets = read_xlsx(&archive(&parts)).unwrap();
+        let (name, df) = &sheets[0];
+        assert_eq!("Sales", name);
+        assert_eq!((7, 4), df.dim());
+
+        let cell = |row, column| df.value(Coordinate{row, column}).unwrap().clone();
+        let date = NaiveDateTime::parse_from_str("20230425 000000", "%Y%m%d %H%M%S").unwrap();
+        assert_eq!(Value::STRING("Name".to_owned()), cell(0, 0));
+        assert_eq!(Value::STRING("Rich text".to_owned()), cell(0, 1));
+        assert_eq!(Value::STRING("007".to_owned()), cell(0, 2));
+        assert_eq!(Value::DATE(date.date(), "%d/%m/%Y".to_owned()), cell(1, 1));
+        assert_eq!(Value::F32(1.5, 2), cell(1, 2));
+        assert_eq!(Value::BOOLEAN(true), cell(2, 1));
+        assert_eq!(Value::ERROR(ErrorKind::VALUE), cell(2, 2));
+        assert_eq!(Value::I32(6), cell(3, 0));
+        assert_eq!(Value::I32(8), cell(4, 0));
+        assert_eq!("=[A2]*2", df.raw(Coordinate{row:4, column:0}).unwrap().to_string());
+        //Functions this crate does not have keep the last calculated value
+        assert_eq!(Value::F32(0.3, 1), cell(3, 2));
+        assert_eq!(Value::STRING("inline".to_owned()), cell(4, 1));
+        assert_eq!(Value::I32(1), cell(6, 3));
+        assert_eq!(Value::NULL(), cell(5, 0));
+    }
+
+    #[test]
+    fn test_errors(){
+        let df = DataFrame::new(vec![]);
+        assert_eq!(Err(XlsxError::InvalidSheetName("a/b".to_owned())), write_xlsx(&[("a/b", &df)]));
+        assert_eq!(Err(XlsxError::InvalidSheetName("data".to_owned())), write_xlsx(&[("Data", &df), ("data", &df)]));
+        assert!(matches!(read_xlsx(b"not a zip"), Err(XlsxError::Archive(_))));
+    }
+
+    #[test]
+    fn test_limits(){
+        //A stray value in the last cell of a spreadsheet is too far out to be read
+        let input = archive(&[
+            ("xl/workbook.xml", r#"<workbook xmlns:r="r"><sheets><sheet name="Far" sheetId="1" r:id="rId1"/></sheets></workbook>"#),
+            ("xl/_rels/workbook.xml.rels", r#"<Relationships><Relationship Id="rId1" Target="worksheets/sheet1.xml"/></Relationships>"#),
+            ("xl/worksheets/sheet1.xml", r#"<worksheet><sheetData><row r="1048576"><c r="XFD1048576"><v>1</v></c></row></sheetData></worksheet>"#)
+        ]);
+        let far = Coordinate{row: 1048575, column: 16383}.to_string();
+        assert_eq!(Some(XlsxError::InvalidCell(far)), read_xlsx(&input).err());
+
+        //Parts are refused when they declare or unpack to more than the limit
+        let content = "x".repeat(100);
+        assert_eq!(Ok(content.clone()), limited(&mut content.as_bytes(), "part", 100, 100));
+        assert!(matches!(limited(&mut content.as_bytes(), "part", 101, 100), Err(XlsxError::Archive(_))));
+        assert!(matches!(limited(&mut content.as_bytes(), "part", 10, 99), Err(XlsxError::Archive(_))));
+    }
+}
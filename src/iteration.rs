@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use crate::coordinate::Coordinate;
+use crate::dependencies::Graph;
+use crate::value::Value;
+use crate::DataFrame;
+
+//Settings for solving intentional circular references by evaluating them until their values stop changing
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Serialize)]
+#[derive(Deserialize)]
+pub struct IterativeCalculation{
+    pub max_iterations: usize,
+    pub epsilon: f64
+}
+
+impl Default for IterativeCalculation {
+    //The same defaults as desktop spreadsheets
+    fn default() -> Self {IterativeCalculation{max_iterations: 100, epsilon: 0.001}}
+}
+
+//The outcome of iterating one group of circular cells
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Convergence{
+    pub cells: Vec<Coordinate>,
+    pub iterations: usize,
+    pub converged: bool
+}
+
+impl DataFrame {
+    pub fn set_iterative_calculation(&mut self, settings: Option<IterativeCalculation>) {
+        /*
+        Turns iterative calculation on (Some) or off (None) and recalculates every circular reference with the new setting
+         */
+        self.iterative = settings;
+        let cells: Vec<Coordinate> = self.cycles.iter().flatten().cloned()
+            .chain(self.convergence.iter().flat_map(|result| result.cells.clone()))
+            .collect();
+        self.recalculate(&cells);
+    }
+
+    pub fn convergence(&self) -> &[Convergence] {
+        //How each group of circular cells was solved when iterative calculation is on
+        &self.convergence
+    }
+
+    pub fn converged(&self) -> bool {
+        self.convergence.iter().all(|result| result.converged)
+    }
+}
+
+pub(crate) fn iterate<G: Graph>(graph: &mut G, cells: &[G::Cell], settings: IterativeCalculation) -> (usize, bool) {
+    /*
+    Evaluates the cells of a loop in order, each using the latest values of the others, until no number changes by
+    more than epsilon between two passes or the maximum iterations is reached. Cells start from their last value,
+    with errors (such as a previous #CYCLE!) starting from empty. Returns the passes made and whether it converged.
+     */
+    for cell in cells {
+        if matches!(graph.solved_mut(cell), Value::ERROR(_)) {
+            *graph.solved_mut(cell) = Value::NULL();
+        }
+    }
+
+    let mut iterations = 0;
+    let mut converged = false;
+    while iterations < settings.max_iterations && !converged {
+        iterations += 1;
+        converged = true;
+        for cell in cells {
+            let value = graph.solve(cell);
+            let previous = std::mem::replace(graph.solved_mut(cell), value);
+            if !within_epsilon(&previous, graph.solved_mut(cell), settings.epsilon) {
+                converged = false;
+            }
+        }
+    }
+
+    (iterations, converged)
+}
+
+fn within_epsilon(previous: &Value, current: &Value, epsilon: f64) -> bool {
+    //Numbers only have to be close, any other value has to be unchanged
+    match (previous.as_f64(), current.as_f64()) {
+        (Some(previous), Some(current)) => (previous - current).abs() <= epsilon,
+        _ => previous == current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::iteration::IterativeCalculation;
+    use crate::test_helpers::coordinate;
+    use crate::value::Value;
+    use crate::DataFrame;
+
+    fn as_f64(value: Option<&Value>) -> f64 {
+        match value.and_then(Value::as_f64) {
+            Some(val) => val,
+            None => panic!("Expected a number, got {:?}", value)
+        }
+    }
+
+    #[test]
+    fn test_interest_on_average_balance(){
+        //Closing balance = opening + interest, interest = 10% of the average of opening and closing balance
+        let mut input = DataFrame::new(
+          vec![
+            vec!["1000".to_string(), "=[A0]+[C0]".to_string(), "=([A0]+[B0])/2*0.1".to_string(), "=[B0]*2".to_string()]
+          ]
+        );
+        assert_eq!(Some(&Value::ERROR(ErrorKind::CYCLE)), input.value(coordinate(0, 1)));
+
+        input.set_iterative_calculation(Some(IterativeCalculation::default()));
+        assert!(input.converged());
+        assert_eq!(1, input.convergence().len());
+        assert_eq!(vec![coordinate(0, 1), coordinate(0, 2)], input.convergence()[0].cells);
+
+        //Closing = 1000 * 1.05 / 0.95
+        assert!((as_f64(input.value(coordinate(0, 1))) - 1105.263).abs() < 0.01);
+        assert!((as_f64(input.value(coordinate(0, 3))) - 2210.526).abs() < 0.02);
+
+        //Edits to the inputs of a loop are iterated again
+        input.set_cell(coordinate(0, 0), Value::I32(2000)).unwrap();
+        assert!((as_f64(input.value(coordinate(0, 1))) - 2210.526).abs() < 0.02);
+        assert_eq!(1, input.convergence().len());
+
+        //Turning it off makes the loop an error again
+        input.set_iterative_calculation(None);
+        assert_eq!(Some(&Value::ERROR(ErrorKind::CYCLE)), input.value(coordinate(0, 1)));
+        assert!(input.convergence().is_empty());
+        assert_eq!(1, input.cycles().len());
+    }
+
+    #[test]
+    fn test_not_converging(){
+        let mut input = DataFrame::new(vec![vec!["=[A0]+1".to_string()]]);
+        input.set_iterative_calculation(Some(IterativeCalculation{max_iterations: 10, epsilon: 0.001}));
+
+        assert!(!input.converged());
+        assert_eq!(10, input.convergence()[0].iterations);
+        assert_eq!(Some(&Value::I32(10)), input.value(coordinate(0, 0)));
+    }
+}
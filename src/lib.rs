@@ -4,21 +4,38 @@ extern crate wasm_bindgen;
 use wasm_bindgen::prelude::*;
 use strum_macros::EnumString;
 use std::str::FromStr;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use core::fmt;
-use ndarray::AssignElem;
 
 mod value;
+mod error;
 mod coordinate;
+mod range;
 mod functions;
+mod parser;
+mod dependencies;
+mod iteration;
+mod api;
+mod table;
+mod shift;
+mod fill;
+mod apply;
+mod monetary;
+pub mod datetime;
+pub mod workbook;
+pub mod io;
+#[cfg(test)]
+mod test_helpers;
 
 
 use ndarray::prelude::*;
-use wasm_bindgen::describe::FUNCTION;
 use crate::value::{solve_function, Value};
+use crate::functions::logical::{is_logical, logical};
 use crate::coordinate::Coordinate;
-use crate::functions::concat::concat;
-use crate::functions::add::add;
+use crate::error::ErrorKind;
+use crate::datetime::DateParsing;
+use crate::iteration::{Convergence, IterativeCalculation};
+use crate::range::Range;
 
 
 #[derive(Clone)]
@@ -28,23 +45,45 @@ use crate::functions::add::add;
 pub enum FUNCTION{
     ADD,
     MOD,
-    CONCAT
+    CONCAT,
+    SUB,
+    MUL,
+    DIV,
+    POW,
+    NEG,
+    EQ,
+    NE,
+    LT,
+    LTE,
+    GT,
+    GTE,
+    SUM,
+    AVERAGE,
+    MIN,
+    MAX,
+    COUNT,
+    COUNTA,
+    PRODUCT,
+    ABS,
+    ROUND,
+    FLOOR,
+    CEIL,
+    SQRT,
+    IF,
+    IFS,
+    AND,
+    OR,
+    NOT,
+    XOR,
+    SWITCH,
+    IFERROR,
+    IFNA,
+    DECIMAL
 }
 impl FUNCTION{
-    fn starts_with(value: &str) -> Result<FUNCTION, &str>{
-        /* Attempts to convert any given string to a Function ENUM, tries to split on first "("
-        then parse that into a enum */
-
-        //Get String before first occurance of '('
-        let mut splitter = value.splitn(2, '(');
-        let first = splitter.next().unwrap().trim();
-
-        //Parse into Enum
-        if let Ok(res) = FUNCTION::from_str(first){
-            return Ok(res)
-        }
-
-        return Err("Error, not a valid Function")
+    fn from_name(name: &str) -> Option<FUNCTION>{
+        /* Attempts to convert a function name as typed in a formula to a Function ENUM, names are case insensitive */
+        FUNCTION::from_str(&name.to_uppercase()).ok()
     }
 }
 impl fmt::Display for FUNCTION {
@@ -58,7 +97,25 @@ impl fmt::Display for FUNCTION {
 #[derive(Debug)]
 pub struct DataFrame{
     data: Array2<Value>,
-    references: HashMap<Coordinate, Vec<Coordinate>>
+    //For each cell, the cells whose formulas reference it (its dependents)
+    references: HashMap<Coordinate, Vec<Coordinate>>,
+    //For each formula cell, the cells it references
+    precedents: HashMap<Coordinate, Vec<Coordinate>>,
+    //Cache of every cell solved to a simple value
+    solved: Array2<Value>,
+    //Circular references found while solving
+    cycles: Vec<Vec<Coordinate>>,
+    //When set, circular references are solved by repeated evaluation instead of being errors
+    iterative: Option<IterativeCalculation>,
+    convergence: Vec<Convergence>,
+    //Widest a cell is shown when printing, longer text is cut short
+    max_width: usize,
+    //Free form details saved with the DataFrame, such as its title
+    metadata: BTreeMap<String, String>,
+    //Decimal places of each column declared as money, numbers typed into them are read as DECIMALs
+    monetary: BTreeMap<usize, usize>,
+    //The date formats typed input is read with
+    dates: DateParsing
 }
 
 impl DataFrame {
@@ -66,16 +123,124 @@ impl DataFrame {
         //Try parse each value in array2 to their corresponding value enum.
         //Trys to assume from string, need a way to parse inputs as a certain type.
 
-        let mut data_transformed = Array2::<Value>::default((data.len(), data[0].len()));
+        //Rows shorter than the longest row are padded with empty cells
+        let columns = data.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut data_transformed = Array2::<Value>::default((data.len(), columns));
         for (i, mut row) in data_transformed.axis_iter_mut(Axis(0)).enumerate() {
-            for (j, col) in row.iter_mut().enumerate() {
+            for (j, col) in row.iter_mut().enumerate().take(data[i].len()) {
                 *col = Value::create_from_str(data[i][j].to_string());
             }
         }
 
-        Self {
-            data: data_transformed,
-            references: HashMap::new()
+        DataFrame::from_values(data_transformed)
+    }
+
+    pub(crate) fn from_values(data: Array2<Value>) -> Self {
+        /*
+        Creates a DataFrame from already parsed cells and solves it
+         */
+        let mut df = Self {
+            solved: Array2::<Value>::default(data.dim()),
+            data,
+            references: HashMap::new(),
+            precedents: HashMap::new(),
+            cycles: vec![],
+            iterative: None,
+            convergence: vec![],
+            max_width: table::DEFAULT_MAX_WIDTH,
+            metadata: BTreeMap::new(),
+            monetary: BTreeMap::new(),
+            dates: DateParsing::default()
+        };
+
+        df.rebuild();
+        df
+    }
+
+    pub(crate) fn rebuild(&mut self) {
+        /*
+        Builds the dependency graph from scratch then solves every cell, for new data or after cells have moved
+         */
+        self.references.clear();
+        self.precedents.clear();
+        self.cycles.clear();
+        self.convergence.clear();
+        self.solved = Array2::<Value>::default(self.data.dim());
+
+        let cells: Vec<Coordinate> = self.data.indexed_iter().map(|((row, column), _)| Coordinate{row, column}).collect();
+        for coordinate in &cells {
+            self.link(*coordinate);
+        }
+        self.recalculate(&cells);
+    }
+
+    pub fn value(&self, coordinate: Coordinate) -> Option<&Value> {
+        //The solved value of a cell
+        self.solved.get((coordinate.row, coordinate.column))
+    }
+
+    pub fn display(&self, coordinate: Coordinate) -> Option<String> {
+        //The solved value of a cell as the text shown in it
+        self.value(coordinate).map(Value::display)
+    }
+
+    pub fn set_max_width(&mut self, max_width: usize) {
+        //Widest a cell is shown when printing the DataFrame, at least one character
+        self.max_width = max_width.max(1);
+    }
+
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    pub fn set_metadata(&mut self, key: &str, value: &str) {
+        self.metadata.insert(key.to_owned(), value.to_owned());
+    }
+
+    pub fn raw(&self, coordinate: Coordinate) -> Option<&Value> {
+        //The value of a cell as it was entered, before solving any formula
+        self.data.get((coordinate.row, coordinate.column))
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        //Number of (rows, columns)
+        self.data.dim()
+    }
+
+    pub fn set_cell_from_str(&mut self, coordinate: Coordinate, input: &str) -> Result<Vec<Coordinate>, String> {
+        /*
+        Parses user input into a cell, an input starting with '=' that is not a valid formula is rejected with the
+        position of the syntax error
+         */
+        self.check_bounds(coordinate)?;
+        let value = self.parse_input(coordinate, input)?;
+        self.set_cell(coordinate, value).map_err(|kind| kind.to_string())
+    }
+
+    pub(crate) fn parse_input(&self, coordinate: Coordinate, input: &str) -> Result<Value, String> {
+        //Input typed into a cell, read with the date formats of the DataFrame and the type of the cell's column
+        let value = Value::try_from_str_with(input, &self.dates).map_err(|error| format!("Invalid formula, {}", error))?;
+        Ok(self.typed(coordinate, input, value))
+    }
+
+    pub fn evaluate(&self, corner_1: Coordinate, corner_2: Coordinate) -> Result<Array2<Value>, String> {
+        /*
+        The solved values between two opposite corners (inclusive), both of which have to be inside the DataFrame
+         */
+        self.check_bounds(corner_1)?;
+        self.check_bounds(corner_2)?;
+        match Range::cells(corner_1, corner_2) {
+            Range::Cells(start, end) => Ok(self.simplify(start, end)),
+            _ => unreachable!("Range::cells always creates a range of cells")
+        }
+    }
+
+    fn check_bounds(&self, coordinate: Coordinate) -> Result<(), String> {
+        let (rows, columns) = self.dim();
+        if coordinate.row < rows && coordinate.column < columns {
+            Ok(())
+        } else {
+            Err(format!("Cell {} is outside of the DataFrame, which has {} rows and {} columns", coordinate, rows, columns))
         }
     }
 
@@ -85,45 +250,93 @@ impl DataFrame {
         Simple values  in this case being anything but cell references and function
          */
 
-        // Get the slice of data we care about
-        let mut binding = self.data.clone();
-        let mut printing_slice = binding.slice_mut(s!(coords1.row..coords2.row+1, coords1.column..coords2.column+1));
-
-        //Solve all the values in the slice to non complex data
-
-        return Array2::from_shape_vec( printing_slice.dim(),
-                                    printing_slice.into_iter()
-                                    .map(|v| self.solve_cell(v))
-                                    .collect::<Vec<Value>>()
-            ).unwrap();
+        // Get the slice of solved data we care about
+        self.solved.slice(s!(coords1.row..coords2.row+1, coords1.column..coords2.column+1)).to_owned()
     }
 
     fn solve_cell(&self, cell: &Value) -> Value {
+        self.solve_in(cell, &[])
+    }
+
+    pub(crate) fn solve_in(&self, cell: &Value, sheets: &[(String, DataFrame)]) -> Value {
         /*
-        Recursive function which solves cell references and functions until a simple datatype is returned.
-        In this context a simple datatype is any value that is not a cell reference or function
+        Solves a cell to a simple datatype, in this context a simple datatype is any value that is not a cell
+        reference or function. Referenced cells are read from the solved cache, so they must be solved first.
+        References to other sheets are looked up by name in sheets, a sheet that is not there is a #REF! error.
          */
 
         //This clones the values sot ehy can be inserted into DF if required without de-referencing
-        return match cell {
-            Value::CELL_REFERENCE(_) => self.solve_cell(cell.solve_reference(&self.data).unwrap()).clone(),
-            Value::FUNCTION(function, values) => solve_function(function, &(values.into_iter().map(|v| self.solve_cell(v)).collect::<Vec<Value>>())).unwrap(),
+        match cell {
+            Value::CELL_REFERENCE(_, _) => match cell.solve_reference(&self.solved) {
+                Some(referenced) => referenced.clone(),
+                None => Value::ERROR(ErrorKind::REF)
+            },
+            Value::RANGE(range, _) => Value::ARRAY(self.solve_range(range)),
+            Value::SHEET_REFERENCE(sheet, reference) => match workbook::find_sheet(sheets, sheet) {
+                Some(index) => sheets[index].1.solve_in(reference, sheets),
+                None => Value::ERROR(ErrorKind::REF)
+            },
+            //Logical functions only solve the arguments they need
+            Value::FUNCTION(function, values) if is_logical(function) =>
+                logical(function, values, &|value| self.solve_in(value, sheets)).unwrap_or_else(Value::ERROR),
+            Value::FUNCTION(function, values) => solve_function(function, &(values.iter().map(|v| self.solve_in(v, sheets)).collect::<Vec<Value>>()))
+                .unwrap_or_else(Value::ERROR),
             _ => cell.clone()
-        };
+        }
+    }
+
+    fn solve_range(&self, range: &Range) -> Array2<Value> {
+        /*
+        Expands a range into a 2-D array of solved values. Only the part of the range inside the data is expanded, the
+        cells past its edge would all be empty and every aggregate skips empty cells.
+         */
+        let (start, _) = range.bounds(self.data.dim());
+        Array2::from_shape_fn(range.shape(self.data.dim()), |(i, j)| {
+            self.solved.get((start.row + i, start.column + j)).cloned().unwrap_or_default()
+        })
+    }
+}
+
+impl fmt::Display for DataFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        table::write_table(f, &self.solved, self.max_width)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
     use crate::value::Value;
     use ndarray::prelude::*;
-    use crate::{DataFrame, FUNCTION};
+    use crate::DataFrame;
     use crate::coordinate::Coordinate;
+    use crate::error::ErrorKind;
 
     #[test]
     fn test_df_print() {
-        assert!(false)
+        let mut input = DataFrame::new(vec![
+            vec!["1".to_string(), "text".to_string(), "20230425 130000".to_string()],
+            vec!["2.50".to_string(), "a much longer piece of text".to_string(), "".to_string()],
+            vec!["=[A0]+[A1]".to_string(), "=1/0".to_string(), "=[A0]*-10".to_string()],
+        ]);
+        let expected = [
+            "  | A    | B                    | C",
+            "--+------+----------------------+----------------",
+            "0 |    1 | text                 | 20230425 130000",
+            "1 | 2.50 | a much longer piece… |",
+            "2 | 3.50 | #DIV/0!              |             -10\n"
+        ].join("\n");
+        assert_eq!(expected, input.to_string());
+
+        input.set_max_width(6);
+        let expected = [
+            "  | A    | B      | C",
+            "--+------+--------+-------",
+            "0 |    1 | text   | 20230…",
+            "1 | 2.50 | a muc… |",
+            "2 | 3.50 | #DIV/… |    -10\n"
+        ].join("\n");
+        assert_eq!(expected, input.to_string());
     }
 
     #[test]
@@ -132,7 +345,7 @@ mod tests {
         /*
         Create the input values
          */
-        let mut input = DataFrame::new(
+        let input = DataFrame::new(
           vec![
             vec!["3".to_string()],   //A1
             vec!["ADD([A0],[A0])".to_string()], //A2
@@ -161,10 +374,216 @@ mod tests {
             data: arr2(&[[Value::I32(3)],   //A1
                         [Value::I32(6)],    //A2
                         [Value::I32(9)]]),   //A3
-            references: HashMap::new()
+            references: HashMap::new(),
+            precedents: HashMap::new(),
+            solved: Array2::default((0, 0)),
+            cycles: vec![],
+            iterative: None,
+            convergence: vec![],
+            max_width: crate::table::DEFAULT_MAX_WIDTH,
+            metadata: BTreeMap::new(),
+            monetary: BTreeMap::new(),
+            dates: crate::datetime::DateParsing::default()
         };
 
         assert_eq!(expected.data, input.simplify(Coordinate{row:0,column:0}, Coordinate{row:2,column:0}));
     }
-}
 
+    #[test]
+    fn test_simplify_operators(){
+        let input = DataFrame::new(
+          vec![
+            vec!["3".to_string(), "1.5".to_string()],
+            vec!["=[A0]+[B0]*2".to_string(), "=-[A0]^2".to_string()],
+            vec!["=[A0]/2".to_string(), "=[A0]&\"x\"".to_string()],
+            vec!["=[A0]>=[B0]".to_string(), "=([A0]-1)*(2-[B0])".to_string()],
+            vec!["=([A0]>2)+TRUE".to_string(), "=[A3]&\"!\"".to_string()]
+          ]
+        );
+
+        let expected = arr2(&[[Value::I32(3), Value::F32(1.5, 1)],
+                             [Value::F32(6.0, 1), Value::I32(-9)],
+                             [Value::F32(1.5, 1), Value::STRING("3x".to_owned())],
+                             [Value::BOOLEAN(true), Value::F32(1.0, 1)],
+                             [Value::I32(2), Value::STRING("TRUE!".to_owned())]]);
+
+        assert_eq!(expected, input.simplify(Coordinate{row:0,column:0}, Coordinate{row:4,column:1}));
+    }
+
+    #[test]
+    fn test_solve_range(){
+        let input = DataFrame::new(
+          vec![
+            vec!["1".to_string(), "2".to_string()],
+            vec!["=[A0]+10".to_string(), "".to_string()],
+            vec!["x".to_string(), "2.5".to_string()]
+          ]
+        );
+
+        let expected = Value::ARRAY(arr2(&[[Value::I32(1), Value::I32(2)],
+                                           [Value::I32(11), Value::NULL()]]));
+        assert_eq!(expected, input.solve_cell(&Value::create_from_str("[A0:B1]".to_string())));
+
+        let expected = Value::ARRAY(arr2(&[[Value::I32(2)], [Value::NULL()], [Value::F32(2.5, 1)]]));
+        assert_eq!(expected, input.solve_cell(&Value::create_from_str("[B:B]".to_string())));
+
+        let expected = Value::ARRAY(arr2(&[[Value::I32(11), Value::NULL()]]));
+        assert_eq!(expected, input.solve_cell(&Value::create_from_str("[1:1]".to_string())));
+
+        //Cells past the edge of the data are left out
+        let expected = Value::ARRAY(arr2(&[[Value::F32(2.5, 1)]]));
+        assert_eq!(expected, input.solve_cell(&Value::create_from_str("[B2:C3]".to_string())));
+    }
+
+    #[test]
+    fn test_simplify_huge_ranges(){
+        //Ranges reaching far past a one row sheet are only expanded as far as the data goes
+        let input = DataFrame::new(vec![vec!["2".to_string(), "=SUM([A0:A100000000])".to_string(), "=SUM([A0:A18446744073709551615])".to_string()]]);
+        assert_eq!(Some(&Value::I32(2)), input.value(Coordinate{row: 0, column: 1}));
+        assert_eq!(Some(&Value::I32(2)), input.value(Coordinate{row: 0, column: 2}));
+    }
+
+    #[test]
+    fn test_simplify_aggregates(){
+        let input = DataFrame::new(
+          vec![
+            vec!["1".to_string(), "SUM([A:A])".to_string()],
+            vec!["2.50".to_string(), "AVERAGE([A0:A4], 4)".to_string()],
+            vec!["".to_string(), "MAX([A0:A3])".to_string()],
+            vec!["text".to_string(), "=COUNT([A:A])+COUNTA([A:A])*10".to_string()],
+            vec!["4".to_string(), "PRODUCT([A0:A1], MIN([A:A]), 2)".to_string()]
+          ]
+        );
+
+        let expected = arr2(&[[Value::F32(7.5, 2)],
+                             [Value::F32(2.875, 3)],
+                             [Value::F32(2.5, 2)],
+                             [Value::I32(43)],
+                             [Value::F32(5.0, 4)]]);
+
+        assert_eq!(expected, input.simplify(Coordinate{row:0,column:1}, Coordinate{row:4,column:1}));
+    }
+
+    #[test]
+    fn test_simplify_arithmetic_functions(){
+        let input = DataFrame::new(
+          vec![
+            vec!["-7".to_string(), "MOD([A0],3)".to_string()],
+            vec!["2.345".to_string(), "ROUND([A1],2)".to_string()],
+            vec!["16".to_string(), "=SQRT([A2])+ABS([A0])".to_string()],
+            vec!["".to_string(), "=FLOOR([A1])&\"/\"&CEIL([A1],0.5)".to_string()],
+            vec!["-2".to_string(), "=-[A3]+ABS([A3])+SQRT([A3])+ROUND([A3])-ABS(\"-2\")".to_string()]
+          ]
+        );
+
+        let expected = arr2(&[[Value::I32(2)],
+                             [Value::F32(2.35, 2)],
+                             [Value::I32(11)],
+                             [Value::STRING("2/2.5".to_owned())],
+                             [Value::I32(-2)]]);
+
+        assert_eq!(expected, input.simplify(Coordinate{row:0,column:1}, Coordinate{row:4,column:1}));
+    }
+
+    #[test]
+    fn test_simplify_errors(){
+        //Errors show in the cell they happen in and every cell depending on it instead of panicking
+        let input = DataFrame::new(
+          vec![
+            vec!["0".to_string(), "=1/[A0]".to_string()],
+            vec!["text".to_string(), "=[B0]+1".to_string()],
+            vec!["".to_string(), "=[A1]*2".to_string()],
+            vec!["=[Z99]".to_string(), "=SUM([A:A])".to_string()],
+            vec!["=NOPE([A0])".to_string(), "=[A2]+1".to_string()]
+          ]
+        );
+
+        let expected = arr2(&[[Value::I32(0), Value::ERROR(ErrorKind::DIV0)],
+                             [Value::STRING("text".to_owned()), Value::ERROR(ErrorKind::DIV0)],
+                             [Value::NULL(), Value::ERROR(ErrorKind::VALUE)],
+                             [Value::ERROR(ErrorKind::REF), Value::ERROR(ErrorKind::REF)],
+                             [Value::ERROR(ErrorKind::NAME), Value::I32(1)]]);
+
+        assert_eq!(expected, input.simplify(Coordinate{row:0,column:0}, Coordinate{row:4,column:1}));
+    }
+
+    #[test]
+    fn test_simplify_logical(){
+        //Branches that are not taken are never solved, so their errors do not show
+        let mut input = DataFrame::new(
+          vec![
+            vec!["0".to_string(), "=IF([A0]=0, 0, 1/[A0])".to_string()],
+            vec!["=1/[A0]".to_string(), "=IFERROR([A1], -1)".to_string()],
+            vec!["=AND([A0]<>0, 1/[A0]>1)".to_string(), "=IFS([A0]>0, \"up\", [A0]<0, \"down\")".to_string()],
+            vec!["=SWITCH([A0], 1, \"one\", 0, \"zero\")".to_string(), "=IFNA([B2], \"flat\")".to_string()]
+          ]
+        );
+
+        let expected = arr2(&[[Value::I32(0), Value::I32(0)],
+                             [Value::ERROR(ErrorKind::DIV0), Value::I32(-1)],
+                             [Value::BOOLEAN(false), Value::ERROR(ErrorKind::NA)],
+                             [Value::STRING("zero".to_owned()), Value::STRING("flat".to_owned())]]);
+        assert_eq!(expected, input.simplify(Coordinate{row:0,column:0}, Coordinate{row:3,column:1}));
+
+        input.set_cell(Coordinate{row:0, column:0}, Value::I32(4)).unwrap();
+        assert_eq!(Some(&Value::F32(0.25, 2)), input.value(Coordinate{row:0, column:1}));
+        assert_eq!(Some(&Value::STRING("up".to_owned())), input.value(Coordinate{row:3, column:1}));
+    }
+
+    #[test]
+    fn test_simplify_decimal(){
+        //DECIMAL() makes any number exact, so sums of amounts do not pick up float noise
+        let input = DataFrame::new(
+          vec![
+            vec!["0.1".to_string(), "=DECIMAL([A0])+0.2".to_string(), "=DECIMAL(\"10.00\")/3".to_string()],
+            vec!["=DECIMAL([A0], 3)".to_string(), "=DECIMAL(\"x\")".to_string(), "=[B0]=0.3".to_string()]
+          ]
+        );
+
+        let expected = arr2(&[[Value::F32(0.1, 1), Value::DECIMAL(3, 1), Value::DECIMAL(333, 2)],
+                             [Value::DECIMAL(100, 3), Value::ERROR(ErrorKind::VALUE), Value::BOOLEAN(true)]]);
+        assert_eq!(expected, input.simplify(Coordinate{row:0,column:0}, Coordinate{row:1,column:2}));
+        assert_eq!("3.33", input.display(Coordinate{row:0, column:2}).unwrap());
+    }
+
+    #[test]
+    fn test_set_cell_from_str(){
+        let mut input = DataFrame::new(vec![vec!["1".to_string(), "=[A0]+1".to_string()]]);
+
+        input.set_cell_from_str(Coordinate{row:0,column:0}, "5").unwrap();
+        assert_eq!(Some(&Value::I32(6)), input.value(Coordinate{row:0,column:1}));
+        assert_eq!(Some(&Value::I32(5)), input.raw(Coordinate{row:0,column:0}));
+
+        //Broken formulas and cells outside of the DataFrame are rejected and leave the cell unchanged
+        assert!(input.set_cell_from_str(Coordinate{row:0,column:0}, "=1+").is_err());
+        assert!(input.set_cell_from_str(Coordinate{row:3,column:0}, "1").is_err());
+        assert_eq!(Some(&Value::I32(5)), input.raw(Coordinate{row:0,column:0}));
+    }
+
+    #[test]
+    fn test_evaluate(){
+        let input = DataFrame::new(vec![vec!["1".to_string(), "2".to_string()],
+                                        vec!["=[A0]+[B0]".to_string(), "".to_string()]]);
+
+        assert_eq!((2, 2), input.dim());
+        assert_eq!(arr2(&[[Value::I32(2)], [Value::NULL()]]),
+                   input.evaluate(Coordinate{row:1,column:1}, Coordinate{row:0,column:1}).unwrap());
+        assert_eq!(arr2(&[[Value::I32(3)]]),
+                   input.evaluate(Coordinate{row:1,column:0}, Coordinate{row:1,column:0}).unwrap());
+        assert!(input.evaluate(Coordinate{row:0,column:0}, Coordinate{row:2,column:0}).is_err());
+    }
+
+    #[test]
+    fn test_empty(){
+        assert_eq!((0, 0), DataFrame::new(vec![]).dim());
+    }
+
+    #[test]
+    fn test_ragged(){
+        let input = DataFrame::new(vec![vec!["1".to_string()], vec![], vec!["2".to_string(), "3".to_string()]]);
+        let expected = arr2(&[[Value::I32(1), Value::NULL()],
+                             [Value::NULL(), Value::NULL()],
+                             [Value::I32(2), Value::I32(3)]]);
+        assert_eq!(expected, input.simplify(Coordinate{row:0,column:0}, Coordinate{row:2,column:1}));
+    }
+}
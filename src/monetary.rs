@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+use crate::coordinate::Coordinate;
+use crate::functions::decimal::{parse, rescale, text, to_decimal, Rounding};
+use crate::value::Value;
+use crate::DataFrame;
+
+impl DataFrame {
+    pub fn set_monetary(&mut self, column: usize, places: Option<usize>) -> Result<Vec<Coordinate>, String> {
+        /*
+        Declares a column as money kept to places decimal places (Some) or clears the declaration (None). Numbers
+        typed into a monetary column are read as exact DECIMALs rounded half to even to places, and the numbers
+        already in it are converted the same way. Clearing it keeps the DECIMALs already there as they are.
+        Returns the recalculated cells in the order they were solved.
+         */
+        let (rows, columns) = self.dim();
+        if column >= columns {
+            return Err(format!("Column {} is outside of the DataFrame, which has {} columns", Coordinate::column_name(column), columns))
+        }
+        let places = match places {
+            Some(places) => places,
+            None => {
+                self.monetary.remove(&column);
+                return Ok(vec![])
+            }
+        };
+        self.monetary.insert(column, places);
+
+        let mut changed = vec![];
+        for row in 0..rows {
+            let coordinate = Coordinate{row, column};
+            if let Some(amount) = money(&self.data[(row, column)], None, places) {
+                self.data[(row, column)] = amount;
+                changed.push(coordinate);
+            }
+        }
+        Ok(self.recalculate(&changed))
+    }
+
+    pub fn monetary(&self) -> &BTreeMap<usize, usize> {
+        //The decimal places of each monetary column
+        &self.monetary
+    }
+
+    pub(crate) fn input(&self, coordinate: Coordinate) -> Option<String> {
+        /*
+        The cell as text which types back in as the same value. An amount in a monetary column is written plainly,
+        e.g. 1200.50, since the column reads it back as the same DECIMAL.
+         */
+        let value = self.raw(coordinate)?;
+        Some(match (value, self.monetary.get(&coordinate.column)) {
+            (Value::DECIMAL(units, places), Some(column)) if places == column => text(*units, *places),
+            _ => value.to_string()
+        })
+    }
+
+    pub(crate) fn typed(&self, coordinate: Coordinate, input: &str, value: Value) -> Value {
+        //A number typed into a monetary column is read as a DECIMAL, anything else is kept as it was parsed
+        match self.monetary.get(&coordinate.column) {
+            Some(places) => money(&value, Some(input), *places).unwrap_or(value),
+            None => value
+        }
+    }
+}
+
+fn money(value: &Value, input: Option<&str>, places: usize) -> Option<Value> {
+    /*
+    A number as a DECIMAL with places decimal places, None for anything that is not a number. The typed input is
+    used when there is one so digits a float could not hold are kept.
+     */
+    if !value.is_number() {
+        return None
+    }
+    let (units, own) = input.and_then(|input| parse(input.trim())).or_else(|| to_decimal(value).ok())?;
+    rescale(units, own, places, Rounding::HalfEven).ok().map(|units| Value::DECIMAL(units, places))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::coordinate;
+    use crate::value::Value;
+    use crate::DataFrame;
+
+    #[test]
+    fn test_monetary(){
+        let mut input = DataFrame::new(vec![
+            vec!["Rent".to_string(), "1200.5".to_string()],
+            vec!["Power".to_string(), "0.1".to_string()],
+            vec!["Total".to_string(), "=SUM([B0:B1])".to_string()]
+        ]);
+        input.set_monetary(1, Some(2)).unwrap();
+        assert_eq!(Some(&Value::DECIMAL(120050, 2)), input.value(coordinate(0, 1)));
+        assert_eq!(Some(&Value::DECIMAL(120060, 2)), input.value(coordinate(2, 1)));
+
+        //Typed numbers are exact and rounded half to even, text and formulas are left alone
+        input.set_cell_from_str(coordinate(1, 1), "12345678901234567.205").unwrap();
+        assert_eq!(Some(&Value::DECIMAL(1234567890123456720, 2)), input.value(coordinate(1, 1)));
+        assert_eq!("12345678901235767.70", input.display(coordinate(2, 1)).unwrap());
+        input.set_cell_from_str(coordinate(1, 1), "n/a").unwrap();
+        assert_eq!(Some(&Value::STRING("n/a".to_owned())), input.value(coordinate(1, 1)));
+
+        //Other columns and cleared columns read numbers as before
+        input.set_cell_from_str(coordinate(1, 0), "2.5").unwrap();
+        assert_eq!(Some(&Value::F32(2.5, 1)), input.value(coordinate(1, 0)));
+        input.set_monetary(1, None).unwrap();
+        input.set_cell_from_str(coordinate(1, 1), "2.5").unwrap();
+        assert_eq!(Some(&Value::F32(2.5, 1)), input.value(coordinate(1, 1)));
+        assert_eq!(Some(&Value::DECIMAL(120050, 2)), input.value(coordinate(0, 1)));
+
+        assert!(input.set_monetary(2, Some(2)).is_err());
+    }
+
+    #[test]
+    fn test_input(){
+        let mut input = DataFrame::new(vec![vec!["1200.5".to_string(), "1200.5".to_string()]]);
+        input.set_monetary(1, Some(2)).unwrap();
+        input.set_cell(coordinate(0, 0), Value::DECIMAL(120050, 2)).unwrap();
+        //Only a monetary column reads plain text back as a DECIMAL
+        assert_eq!(Some("=DECIMAL(\"1200.50\")".to_owned()), input.input(coordinate(0, 0)));
+        assert_eq!(Some("1200.50".to_owned()), input.input(coordinate(0, 1)));
+        input.set_cell_from_str(coordinate(0, 1), "1200.50").unwrap();
+        assert_eq!(Some(&Value::DECIMAL(120050, 2)), input.raw(coordinate(0, 1)));
+        assert_eq!(None, input.input(coordinate(1, 0)));
+    }
+
+    #[test]
+    fn test_monetary_columns_move(){
+        let mut input = DataFrame::new(vec![vec!["1".to_string(), "2".to_string()]]);
+        input.set_monetary(1, Some(2)).unwrap();
+        input.insert_columns(0, 1).unwrap();
+        assert_eq!(Some(&2), input.monetary().get(&2));
+        input.delete_columns(2, 1).unwrap();
+        assert!(input.monetary().is_empty());
+    }
+}
@@ -0,0 +1,73 @@
+use crate::coordinate::{Anchor, Coordinate};
+use crate::error::ErrorKind;
+use crate::parser::Span;
+use crate::range::Range;
+use crate::FUNCTION;
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub enum UnaryOperator{
+    Plus,
+    Minus
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub enum BinaryOperator{
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+    Concat,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum ExprKind{
+    Number(String),
+    Text(String),
+    Boolean(bool),
+    Reference(Coordinate, Anchor),
+    Range(Range, [Anchor; 2]),
+    //A Reference or Range on the named sheet
+    SheetReference(String, Box<ExprKind>),
+    Error(ErrorKind),
+    Function(FUNCTION, Vec<Expr>),
+    Unary(UnaryOperator, Box<Expr>),
+    Binary(BinaryOperator, Box<Expr>, Box<Expr>)
+}
+
+//A node of the formula syntax tree, keeps the span it was parsed from so later stages can report positioned errors
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Expr{
+    pub kind: ExprKind,
+    pub span: Span,
+    //Levels of the tree from this node down, a leaf is 1
+    pub depth: usize
+}
+
+impl Expr{
+    pub fn new(kind: ExprKind, span: Span) -> Self {
+        let depth = 1 + match &kind {
+            ExprKind::Function(_, arguments) => arguments.iter().map(|argument| argument.depth).max().unwrap_or(0),
+            ExprKind::Unary(_, operand) => operand.depth,
+            ExprKind::Binary(_, left, right) => left.depth.max(right.depth),
+            _ => 0
+        };
+        Self {kind, span, depth}
+    }
+}
@@ -0,0 +1,244 @@
+use crate::error::ErrorKind;
+use crate::parser::{ParseError, ParseErrorKind, Span};
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum TokenKind{
+    Number(String),
+    Text(String),
+    Identifier(String),
+    Reference(String),
+    Error(ErrorKind),
+    LeftParen,
+    RightParen,
+    Comma,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    Ampersand,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    End
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Token{
+    pub kind: TokenKind,
+    pub span: Span
+}
+
+pub fn tokenize(formula: &str) -> Result<Vec<Token>, ParseError> {
+    /*
+    Splits a formula into tokens. Spans are character positions (not bytes) into the given string
+    so they can be used to highlight the offending part of the formula.
+     */
+    let chars: Vec<char> = formula.chars().collect();
+    let mut tokens: Vec<Token> = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        if c.is_whitespace() {
+            i += 1;
+            continue
+        }
+
+        let kind = match c {
+            '(' => {i += 1; TokenKind::LeftParen},
+            ')' => {i += 1; TokenKind::RightParen},
+            ',' => {i += 1; TokenKind::Comma},
+            '+' => {i += 1; TokenKind::Plus},
+            '-' => {i += 1; TokenKind::Minus},
+            '*' => {i += 1; TokenKind::Star},
+            '/' => {i += 1; TokenKind::Slash},
+            '^' => {i += 1; TokenKind::Caret},
+            '&' => {i += 1; TokenKind::Ampersand},
+            '=' => {i += 1; TokenKind::Equal},
+            '<' => {
+                i += 1;
+                match chars.get(i) {
+                    Some('=') => {i += 1; TokenKind::LessEqual},
+                    Some('>') => {i += 1; TokenKind::NotEqual},
+                    _ => TokenKind::Less
+                }
+            },
+            '>' => {
+                i += 1;
+                if chars.get(i) == Some(&'=') {
+                    i += 1;
+                    TokenKind::GreaterEqual
+                } else {
+                    TokenKind::Greater
+                }
+            },
+            '"' => {
+                //Strings use a doubled quote ("") to escape a quote, the same as spreadsheets
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some('"') if chars.get(i+1) == Some(&'"') => {text.push('"'); i += 2},
+                        Some('"') => {i += 1; break},
+                        Some(other) => {text.push(*other); i += 1},
+                        None => return Err(ParseError::new(ParseErrorKind::UnterminatedString, Span::new(start, i)))
+                    }
+                }
+                TokenKind::Text(text)
+            },
+            '[' => {
+                //Everything inside the brackets is handed to the parser as is, it knows the reference grammar
+                let mut reference = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some(']') => {i += 1; break},
+                        Some(other) => {reference.push(*other); i += 1},
+                        None => return Err(ParseError::new(ParseErrorKind::UnterminatedReference, Span::new(start, i)))
+                    }
+                }
+                TokenKind::Reference(reference)
+            },
+            '#' => {
+                //Error literals such as #REF! left behind in a formula, matched against the known codes
+                let rest: String = chars[i..].iter().collect::<String>().to_uppercase();
+                match ErrorKind::ALL.into_iter().find(|kind| rest.starts_with(&kind.to_string())) {
+                    Some(kind) => {i += kind.to_string().chars().count(); TokenKind::Error(kind)},
+                    None => return Err(ParseError::new(ParseErrorKind::UnexpectedCharacter(c), Span::new(start, start+1)))
+                }
+            },
+            _ if c.is_ascii_digit() || c == '.' => {
+                let mut number = String::new();
+                while let Some(d) = chars.get(i).filter(|d| d.is_ascii_digit() || **d == '.') {
+                    number.push(*d);
+                    i += 1;
+                }
+
+                //Optional exponent, only consumed when digits follow so "1E" is not swallowed
+                if matches!(chars.get(i), Some('e') | Some('E')) {
+                    let sign = matches!(chars.get(i+1), Some('+') | Some('-')) as usize;
+                    if chars.get(i+1+sign).is_some_and(|d| d.is_ascii_digit()) {
+                        number.extend(&chars[i..i+1+sign]);
+                        i += 1 + sign;
+                        while let Some(d) = chars.get(i).filter(|d| d.is_ascii_digit()) {
+                            number.push(*d);
+                            i += 1;
+                        }
+                    }
+                }
+
+                if number == "." || number.matches('.').count() > 1 {
+                    return Err(ParseError::new(ParseErrorKind::InvalidNumber(number), Span::new(start, i)))
+                }
+                TokenKind::Number(number)
+            },
+            _ if c.is_alphabetic() || c == '_' => {
+                let mut identifier = String::new();
+                while let Some(d) = chars.get(i).filter(|d| d.is_alphanumeric() || **d == '_' || **d == '.') {
+                    identifier.push(*d);
+                    i += 1;
+                }
+                TokenKind::Identifier(identifier)
+            },
+            _ => return Err(ParseError::new(ParseErrorKind::UnexpectedCharacter(c), Span::new(start, start+1)))
+        };
+
+        tokens.push(Token{kind, span: Span::new(start, i)});
+    }
+
+    tokens.push(Token{kind: TokenKind::End, span: Span::new(chars.len(), chars.len())});
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::parser::lexer::{tokenize, TokenKind};
+    use crate::parser::{ParseErrorKind, Span};
+
+    fn kinds(formula: &str) -> Vec<TokenKind> {
+        tokenize(formula).unwrap().into_iter().map(|t| t.kind).collect()
+    }
+
+    #[test]
+    fn test_function_tokens(){
+        assert_eq!(
+            vec![
+                TokenKind::Identifier("ADD".to_owned()),
+                TokenKind::LeftParen,
+                TokenKind::Reference("A1".to_owned()),
+                TokenKind::Comma,
+                TokenKind::Number("2.5".to_owned()),
+                TokenKind::RightParen,
+                TokenKind::End
+            ],
+            kinds("ADD([A1], 2.5)")
+        )
+    }
+
+    #[test]
+    fn test_operator_tokens(){
+        assert_eq!(
+            vec![
+                TokenKind::LessEqual, TokenKind::NotEqual, TokenKind::GreaterEqual,
+                TokenKind::Less, TokenKind::Greater, TokenKind::Equal, TokenKind::Caret,
+                TokenKind::Ampersand, TokenKind::End
+            ],
+            kinds("<= <> >= < > = ^ &")
+        )
+    }
+
+    #[test]
+    fn test_string_escape(){
+        assert_eq!(
+            vec![TokenKind::Text("say \"hi\"".to_owned()), TokenKind::End],
+            kinds("\"say \"\"hi\"\"\"")
+        )
+    }
+
+    #[test]
+    fn test_error_literal(){
+        assert_eq!(
+            vec![TokenKind::Error(ErrorKind::DIV0), TokenKind::Slash, TokenKind::Error(ErrorKind::NA), TokenKind::End],
+            kinds("#div/0!/#N/A")
+        );
+        assert_eq!(ParseErrorKind::UnexpectedCharacter('#'), tokenize("#OOPS").unwrap_err().kind)
+    }
+
+    #[test]
+    fn test_exponent(){
+        assert_eq!(vec![TokenKind::Number("1.5e-3".to_owned()), TokenKind::End], kinds("1.5e-3"))
+    }
+
+    #[test]
+    fn test_spans_are_characters(){
+        let tokens = tokenize("\"é\" & [A1]").unwrap();
+        assert_eq!(Span::new(0, 3), tokens[0].span);
+        assert_eq!(Span::new(4, 5), tokens[1].span);
+        assert_eq!(Span::new(6, 10), tokens[2].span);
+    }
+
+    #[test]
+    fn test_errors(){
+        let error = tokenize("ADD(1, \"abc").unwrap_err();
+        assert_eq!(ParseErrorKind::UnterminatedString, error.kind);
+        assert_eq!(Span::new(7, 11), error.span);
+
+        let error = tokenize("[A1").unwrap_err();
+        assert_eq!(ParseErrorKind::UnterminatedReference, error.kind);
+
+        let error = tokenize("1 # 2").unwrap_err();
+        assert_eq!(ParseErrorKind::UnexpectedCharacter('#'), error.kind);
+        assert_eq!(Span::new(2, 3), error.span);
+    }
+}
@@ -0,0 +1,660 @@
+use std::fmt;
+use crate::coordinate::{Anchor, Coordinate};
+use crate::parser::ast::{BinaryOperator, Expr, ExprKind, UnaryOperator};
+use crate::parser::lexer::{tokenize, Token, TokenKind};
+use crate::range::Range;
+use crate::value::Value;
+use crate::FUNCTION;
+
+pub mod ast;
+pub mod lexer;
+pub mod printer;
+
+//Limits on how deep a formula goes, each level is a level of recursion when the formula is parsed, printed and
+//solved. Nesting counts brackets, function calls, signs and exponents inside each other, depth counts the levels of
+//the parsed tree, where every operator of a chain such as 1+2+3 is a level.
+const MAX_NESTING: usize = 100;
+const MAX_DEPTH: usize = 256;
+
+//Character positions into a formula, end is exclusive
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct Span{
+    pub start: usize,
+    pub end: usize
+}
+
+impl Span{
+    pub fn new(start: usize, end: usize) -> Self {
+        Self {start, end}
+    }
+
+    fn to(self, other: Span) -> Self {
+        Span::new(self.start, other.end)
+    }
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum ParseErrorKind{
+    UnexpectedCharacter(char),
+    UnterminatedString,
+    UnterminatedReference,
+    InvalidNumber(String),
+    InvalidReference(String),
+    UnexpectedToken(String),
+    UnexpectedEnd,
+    UnknownFunction(String),
+    UnknownName(String),
+    TooDeeplyNested
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct ParseError{
+    pub kind: ParseErrorKind,
+    pub span: Span
+}
+
+impl ParseError{
+    pub fn new(kind: ParseErrorKind, span: Span) -> Self {
+        Self {kind, span}
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match &self.kind {
+            ParseErrorKind::UnexpectedCharacter(c) => format!("unexpected character '{}'", c),
+            ParseErrorKind::UnterminatedString => "unterminated string".to_owned(),
+            ParseErrorKind::UnterminatedReference => "unterminated cell reference, missing ']'".to_owned(),
+            ParseErrorKind::InvalidNumber(number) => format!("invalid number '{}'", number),
+            ParseErrorKind::InvalidReference(reference) => format!("invalid cell reference '[{}]'", reference),
+            ParseErrorKind::UnexpectedToken(token) => format!("unexpected {}", token),
+            ParseErrorKind::UnexpectedEnd => "unexpected end of formula".to_owned(),
+            ParseErrorKind::UnknownFunction(name) => format!("unknown function '{}'", name),
+            ParseErrorKind::UnknownName(name) => format!("unknown name '{}'", name),
+            ParseErrorKind::TooDeeplyNested => "formula is nested too deeply".to_owned()
+        };
+        write!(f, "{} at {}..{}", message, self.span.start, self.span.end)
+    }
+}
+
+pub fn parse(formula: &str) -> Result<Value, ParseError> {
+    /*
+    Parses a formula into the Value that gets stored in a cell. A leading '=' is optional.
+     */
+    lower(parse_expression(formula)?)
+}
+
+pub fn parse_expression(formula: &str) -> Result<Expr, ParseError> {
+    /*
+    Parses a formula into its syntax tree. A leading '=' is optional.
+     */
+    let mut tokens = tokenize(formula)?;
+    if tokens[0].kind == TokenKind::Equal {
+        tokens.remove(0);
+    }
+
+    let mut parser = Parser {tokens, position: 0, nesting: 0};
+    let expr = parser.comparison()?;
+    parser.expect_end()?;
+    Ok(expr)
+}
+
+struct Parser{
+    tokens: Vec<Token>,
+    position: usize,
+    //Brackets, function calls, signs and exponents currently being parsed inside each other
+    nesting: usize
+}
+
+impl Parser{
+    /*
+    Recursive descent parser, each method parses one precedence level, lowest first:
+        comparison  = concat (("=" | "<>" | "<" | "<=" | ">" | ">=") concat)*
+        concat      = additive ("&" additive)*
+        additive    = term (("+" | "-") term)*
+        term        = unary (("*" | "/") unary)*
+        unary       = ("+" | "-") unary | power
+        power       = primary ("^" unary)?
+        primary     = number | string | reference | error | function "(" arguments ")" | "(" comparison ")"
+     */
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position]
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.position].clone();
+        if token.kind != TokenKind::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn unexpected(token: &Token) -> ParseError {
+        match &token.kind {
+            TokenKind::End => ParseError::new(ParseErrorKind::UnexpectedEnd, token.span),
+            kind => ParseError::new(ParseErrorKind::UnexpectedToken(describe(kind)), token.span)
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<Token, ParseError> {
+        if self.peek().kind == kind {
+            Ok(self.next())
+        } else {
+            Err(Parser::unexpected(self.peek()))
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<(), ParseError> {
+        self.expect(TokenKind::End).map(|_| ())
+    }
+
+    fn nested(&mut self, span: Span, parse: fn(&mut Parser) -> Result<Expr, ParseError>) -> Result<Expr, ParseError> {
+        //Parses one level further in, failing once the formula is nested deeper than MAX_NESTING
+        if self.nesting >= MAX_NESTING {
+            return Err(ParseError::new(ParseErrorKind::TooDeeplyNested, span))
+        }
+        self.nesting += 1;
+        let expr = parse(self);
+        self.nesting -= 1;
+        expr
+    }
+
+    fn built(kind: ExprKind, span: Span) -> Result<Expr, ParseError> {
+        //A node whose tree is deeper than MAX_DEPTH, such as the end of a long chain of operators, is rejected
+        let expr = Expr::new(kind, span);
+        if expr.depth > MAX_DEPTH {
+            return Err(ParseError::new(ParseErrorKind::TooDeeplyNested, span))
+        }
+        Ok(expr)
+    }
+
+    fn binary(&mut self,
+              operand: fn(&mut Parser) -> Result<Expr, ParseError>,
+              operator: fn(&TokenKind) -> Option<BinaryOperator>
+    ) -> Result<Expr, ParseError> {
+        //Parses a left associative chain of binary operators sharing the same precedence
+        let mut left = operand(self)?;
+        while let Some(op) = operator(&self.peek().kind) {
+            self.next();
+            let right = operand(self)?;
+            let span = left.span.to(right.span);
+            left = Parser::built(ExprKind::Binary(op, Box::new(left), Box::new(right)), span)?;
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        self.binary(Parser::concat, |kind| match kind {
+            TokenKind::Equal => Some(BinaryOperator::Equal),
+            TokenKind::NotEqual => Some(BinaryOperator::NotEqual),
+            TokenKind::Less => Some(BinaryOperator::Less),
+            TokenKind::LessEqual => Some(BinaryOperator::LessEqual),
+            TokenKind::Greater => Some(BinaryOperator::Greater),
+            TokenKind::GreaterEqual => Some(BinaryOperator::GreaterEqual),
+            _ => None
+        })
+    }
+
+    fn concat(&mut self) -> Result<Expr, ParseError> {
+        self.binary(Parser::additive, |kind| match kind {
+            TokenKind::Ampersand => Some(BinaryOperator::Concat),
+            _ => None
+        })
+    }
+
+    fn additive(&mut self) -> Result<Expr, ParseError> {
+        self.binary(Parser::term, |kind| match kind {
+            TokenKind::Plus => Some(BinaryOperator::Add),
+            TokenKind::Minus => Some(BinaryOperator::Subtract),
+            _ => None
+        })
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        self.binary(Parser::unary, |kind| match kind {
+            TokenKind::Star => Some(BinaryOperator::Multiply),
+            TokenKind::Slash => Some(BinaryOperator::Divide),
+            _ => None
+        })
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        let op = match self.peek().kind {
+            TokenKind::Plus => UnaryOperator::Plus,
+            TokenKind::Minus => UnaryOperator::Minus,
+            _ => return self.power()
+        };
+        let start = self.next().span;
+        let operand = self.nested(start, Parser::unary)?;
+        let span = start.to(operand.span);
+        Parser::built(ExprKind::Unary(op, Box::new(operand)), span)
+    }
+
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        //Right associative, 2^3^2 is 2^(3^2)
+        let base = self.primary()?;
+        if self.peek().kind != TokenKind::Caret {
+            return Ok(base)
+        }
+        let caret = self.next().span;
+        let exponent = self.nested(caret, Parser::unary)?;
+        let span = base.span.to(exponent.span);
+        Parser::built(ExprKind::Binary(BinaryOperator::Power, Box::new(base), Box::new(exponent)), span)
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let token = self.next();
+        match token.kind {
+            TokenKind::Number(number) => Ok(Expr::new(ExprKind::Number(number), token.span)),
+            TokenKind::Text(text) => Ok(Expr::new(ExprKind::Text(text), token.span)),
+            TokenKind::Reference(reference) => Ok(Expr::new(parse_reference(&reference, token.span)?, token.span)),
+            TokenKind::Error(kind) => Ok(Expr::new(ExprKind::Error(kind), token.span)),
+            TokenKind::LeftParen => {
+                let mut inner = self.nested(token.span, Parser::comparison)?;
+                let close = self.expect(TokenKind::RightParen)?;
+                inner.span = token.span.to(close.span);
+                Ok(inner)
+            },
+            TokenKind::Identifier(name) => {
+                if self.peek().kind != TokenKind::LeftParen {
+                    //TRUE and FALSE are the only names which are not a function
+                    return match name.to_uppercase().as_str() {
+                        "TRUE" => Ok(Expr::new(ExprKind::Boolean(true), token.span)),
+                        "FALSE" => Ok(Expr::new(ExprKind::Boolean(false), token.span)),
+                        _ => Err(ParseError::new(ParseErrorKind::UnknownName(name), token.span))
+                    }
+                }
+                let function = FUNCTION::from_name(&name)
+                    .ok_or_else(|| ParseError::new(ParseErrorKind::UnknownFunction(name), token.span))?;
+                self.next();
+
+                let mut arguments = vec![];
+                if self.peek().kind != TokenKind::RightParen {
+                    arguments.push(self.nested(token.span, Parser::comparison)?);
+                    while self.peek().kind == TokenKind::Comma {
+                        self.next();
+                        arguments.push(self.nested(token.span, Parser::comparison)?);
+                    }
+                }
+                let close = self.expect(TokenKind::RightParen)?;
+                Parser::built(ExprKind::Function(function, arguments), token.span.to(close.span))
+            },
+            _ => Err(Parser::unexpected(&token))
+        }
+    }
+}
+
+fn describe(kind: &TokenKind) -> String {
+    match kind {
+        TokenKind::Number(number) => format!("number '{}'", number),
+        TokenKind::Text(text) => format!("string \"{}\"", text),
+        TokenKind::Identifier(name) => format!("name '{}'", name),
+        TokenKind::Reference(reference) => format!("reference '[{}]'", reference),
+        TokenKind::Error(kind) => format!("error '{}'", kind),
+        TokenKind::LeftParen => "'('".to_owned(),
+        TokenKind::RightParen => "')'".to_owned(),
+        TokenKind::Comma => "','".to_owned(),
+        TokenKind::Plus => "'+'".to_owned(),
+        TokenKind::Minus => "'-'".to_owned(),
+        TokenKind::Star => "'*'".to_owned(),
+        TokenKind::Slash => "'/'".to_owned(),
+        TokenKind::Caret => "'^'".to_owned(),
+        TokenKind::Ampersand => "'&'".to_owned(),
+        TokenKind::Equal => "'='".to_owned(),
+        TokenKind::NotEqual => "'<>'".to_owned(),
+        TokenKind::Less => "'<'".to_owned(),
+        TokenKind::LessEqual => "'<='".to_owned(),
+        TokenKind::Greater => "'>'".to_owned(),
+        TokenKind::GreaterEqual => "'>='".to_owned(),
+        TokenKind::End => "end of formula".to_owned()
+    }
+}
+
+fn parse_reference(reference: &str, span: Span) -> Result<ExprKind, ParseError> {
+    /*
+    Parses the text between the brackets of a reference, either a single cell "A1", a range of cells "A1:C10",
+    whole columns "A:C" or whole rows "3:5". Any of them can be on another sheet, e.g. "Sheet2!A1" or "'My Sheet'!A:C"
+     */
+    let invalid = || ParseError::new(ParseErrorKind::InvalidReference(reference.to_owned()), span);
+
+    if let Some((sheet, cells)) = reference.rsplit_once('!') {
+        let sheet = parse_sheet_name(sheet.trim()).ok_or_else(invalid)?;
+        return match parse_reference(cells, span) {
+            Ok(kind) => Ok(ExprKind::SheetReference(sheet, Box::new(kind))),
+            Err(_) => Err(invalid())
+        }
+    }
+
+    let (start, end) = match reference.split_once(':') {
+        None => return parse_coordinate(reference.trim())
+            .map(|(coordinate, anchor)| ExprKind::Reference(coordinate, anchor))
+            .ok_or_else(invalid),
+        Some((start, end)) => (start.trim(), end.trim())
+    };
+
+    //Ranges are stored by their top left and bottom right corners, each anchor stays with the row or column it was written on
+    if let (Some((start, first)), Some((end, last))) = (parse_coordinate(start), parse_coordinate(end)) {
+        let (top, bottom) = ordered(start.row <= end.row, first.row, last.row);
+        let (left, right) = ordered(start.column <= end.column, first.column, last.column);
+        let anchors = [Anchor{row: top, column: left}, Anchor{row: bottom, column: right}];
+        return Ok(ExprKind::Range(Range::cells(start, end), anchors))
+    }
+
+    let ((start, first), (end, last)) = (strip_anchor(start), strip_anchor(end));
+    if let (Some(start), Some(end)) = (Coordinate::column_number(start), Coordinate::column_number(end)) {
+        let (left, right) = ordered(start <= end, first, last);
+        let anchors = [Anchor{row: false, column: left}, Anchor{row: false, column: right}];
+        return Ok(ExprKind::Range(Range::columns(start, end), anchors))
+    }
+    if let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) {
+        let (top, bottom) = ordered(start <= end, first, last);
+        let anchors = [Anchor{row: top, column: false}, Anchor{row: bottom, column: false}];
+        return Ok(ExprKind::Range(Range::rows(start, end), anchors))
+    }
+
+    Err(invalid())
+}
+
+fn ordered(in_order: bool, first: bool, last: bool) -> (bool, bool) {
+    if in_order {(first, last)} else {(last, first)}
+}
+
+fn strip_anchor(text: &str) -> (&str, bool) {
+    //A leading $ fixes the column or row that follows it
+    match text.strip_prefix('$') {
+        Some(rest) => (rest, true),
+        None => (text, false)
+    }
+}
+
+fn parse_sheet_name(sheet: &str) -> Option<String> {
+    //A sheet name can be quoted, with quotes inside it doubled, e.g. 'Bob''s Sheet'
+    let name = match sheet.strip_prefix('\'').and_then(|quoted| quoted.strip_suffix('\'')) {
+        Some(quoted) => quoted.replace("''", "'"),
+        None => sheet.to_owned()
+    };
+    if name.is_empty() {None} else {Some(name)}
+}
+
+fn parse_coordinate(cell: &str) -> Option<(Coordinate, Anchor)> {
+    //Column letters followed by the row digits, either can be anchored with a $, e.g. AB12, $AB12 or $AB$12
+    let (cell, column) = strip_anchor(cell);
+    let split = cell.find(|c: char| !c.is_ascii_alphabetic())?;
+    let (letters, digits) = cell.split_at(split);
+    let (digits, row) = strip_anchor(digits);
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None
+    }
+    Some((Coordinate{row: digits.parse().ok()?, column: Coordinate::column_number(letters)?}, Anchor{row, column}))
+}
+
+fn lower(expr: Expr) -> Result<Value, ParseError> {
+    /*
+    Converts the syntax tree into the Value representation the DataFrame evaluates
+     */
+    match expr.kind {
+        ExprKind::Number(ref number) => Value::from_number_str(number)
+            .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidNumber(number.to_owned()), expr.span)),
+        ExprKind::Text(text) => Ok(Value::STRING(text)),
+        ExprKind::Boolean(val) => Ok(Value::BOOLEAN(val)),
+        ExprKind::Reference(coordinate, anchor) => Ok(Value::CELL_REFERENCE(coordinate, anchor)),
+        ExprKind::Range(range, anchors) => Ok(Value::RANGE(range, anchors)),
+        ExprKind::SheetReference(sheet, reference) =>
+            Ok(Value::SHEET_REFERENCE(sheet, Box::new(lower(Expr::new(*reference, expr.span))?))),
+        ExprKind::Error(kind) => Ok(Value::ERROR(kind)),
+        ExprKind::Function(function, arguments) => Ok(Value::FUNCTION(
+            function,
+            arguments.into_iter().map(lower).collect::<Result<Vec<Value>, ParseError>>()?
+        )),
+        ExprKind::Unary(UnaryOperator::Plus, operand) => lower(*operand),
+        ExprKind::Unary(UnaryOperator::Minus, operand) => match operand.kind {
+            //Negative number literals are kept as plain values rather than a negation of a positive value
+            ExprKind::Number(ref number) => Value::from_number_str(&format!("-{}", number))
+                .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidNumber(number.to_owned()), expr.span)),
+            _ => Ok(Value::FUNCTION(FUNCTION::NEG, vec![lower(*operand)?]))
+        },
+        ExprKind::Binary(op, left, right) => {
+            //Operators are evaluated through the same functions as their named equivalent
+            let function = match op {
+                BinaryOperator::Add => FUNCTION::ADD,
+                BinaryOperator::Subtract => FUNCTION::SUB,
+                BinaryOperator::Multiply => FUNCTION::MUL,
+                BinaryOperator::Divide => FUNCTION::DIV,
+                BinaryOperator::Power => FUNCTION::POW,
+                BinaryOperator::Concat => FUNCTION::CONCAT,
+                BinaryOperator::Equal => FUNCTION::EQ,
+                BinaryOperator::NotEqual => FUNCTION::NE,
+                BinaryOperator::Less => FUNCTION::LT,
+                BinaryOperator::LessEqual => FUNCTION::LTE,
+                BinaryOperator::Greater => FUNCTION::GT,
+                BinaryOperator::GreaterEqual => FUNCTION::GTE
+            };
+            Ok(Value::FUNCTION(function, vec![lower(*left)?, lower(*right)?]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coordinate::{Anchor, Coordinate};
+    use crate::parser::ast::{BinaryOperator, ExprKind};
+    use crate::parser::{parse, parse_expression, ParseError, ParseErrorKind, Span};
+    use crate::range::Range;
+    use crate::value::Value;
+    use crate::FUNCTION;
+
+    #[test]
+    fn test_nested_functions(){
+        let expected = Value::FUNCTION(FUNCTION::ADD, vec![
+            Value::FUNCTION(FUNCTION::ADD, vec![Value::I32(1), Value::I32(2)]),
+            Value::I32(3)
+        ]);
+        assert_eq!(expected, parse("ADD(ADD(1,2),3)").unwrap());
+
+        let expected = Value::FUNCTION(FUNCTION::ADD, vec![
+            Value::I32(1),
+            Value::FUNCTION(FUNCTION::ADD, vec![
+                Value::FUNCTION(FUNCTION::ADD, vec![Value::I32(2), Value::CELL_REFERENCE(Coordinate{row:0, column:1}, Anchor::RELATIVE)]),
+                Value::I32(4)
+            ])
+        ]);
+        assert_eq!(expected, parse("=add(1, ADD(ADD(2,[B0]),4))").unwrap());
+    }
+
+    #[test]
+    fn test_literals(){
+        assert_eq!(Value::F32(2.50, 2), parse("2.50").unwrap());
+        assert_eq!(Value::I32(-4), parse("-4").unwrap());
+        assert_eq!(Value::STRING("a, b)".to_owned()), parse("\"a, b)\"").unwrap());
+        assert_eq!(Value::BOOLEAN(true), parse("=true").unwrap());
+        assert_eq!(
+            Value::FUNCTION(FUNCTION::EQ, vec![Value::BOOLEAN(false), Value::CELL_REFERENCE(Coordinate{row:0, column:0}, Anchor::RELATIVE)]),
+            parse("FALSE=[A0]").unwrap()
+        );
+        assert!(matches!(parse("TRUTH").unwrap_err().kind, ParseErrorKind::UnknownName(_)));
+        assert_eq!(
+            Value::FUNCTION(FUNCTION::CONCAT, vec![Value::STRING("x".to_owned()), Value::CELL_REFERENCE(Coordinate{row:3, column:0}, Anchor::RELATIVE)]),
+            parse("CONCAT(\"x\",[a3])").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_ast(){
+        let expr = parse_expression("=1+[A1]*2").unwrap();
+        assert_eq!(Span::new(1, 9), expr.span);
+        match expr.kind {
+            ExprKind::Binary(BinaryOperator::Add, left, right) => {
+                assert_eq!(ExprKind::Number("1".to_owned()), left.kind);
+                assert!(matches!(right.kind, ExprKind::Binary(BinaryOperator::Multiply, _, _)));
+            },
+            _ => panic!("Expected an addition")
+        }
+
+        let expr = parse_expression("[A1:C10]").unwrap();
+        assert_eq!(ExprKind::Range(Range::cells(Coordinate{row:1, column:0}, Coordinate{row:10, column:2}), [Anchor::RELATIVE; 2]), expr.kind);
+    }
+
+    #[test]
+    fn test_ranges(){
+        assert_eq!(Value::RANGE(Range::cells(Coordinate{row:1, column:0}, Coordinate{row:10, column:2}), [Anchor::RELATIVE; 2]), parse("[A1:C10]").unwrap());
+        assert_eq!(Value::RANGE(Range::cells(Coordinate{row:1, column:0}, Coordinate{row:10, column:2}), [Anchor::RELATIVE; 2]), parse("[C1:A10]").unwrap());
+        assert_eq!(Value::RANGE(Range::columns(0, 0), [Anchor::RELATIVE; 2]), parse("[A:A]").unwrap());
+        assert_eq!(Value::RANGE(Range::columns(1, 3), [Anchor::RELATIVE; 2]), parse("[d:b]").unwrap());
+        assert_eq!(Value::RANGE(Range::rows(3, 3), [Anchor::RELATIVE; 2]), parse("[3:3]").unwrap());
+        assert_eq!(
+            Value::FUNCTION(FUNCTION::ADD, vec![Value::RANGE(Range::rows(1, 2), [Anchor::RELATIVE; 2]), Value::I32(1)]),
+            parse("ADD([1:2], 1)").unwrap()
+        );
+
+        for reference in ["[A1:3]", "[A:1]", "[A1:B2:C3]", "[:]", "[A1:]", "[!A1]", "[Sheet2!]", "['Sheet2'!A]"] {
+            assert!(matches!(parse(reference).unwrap_err().kind, ParseErrorKind::InvalidReference(_)), "{}", reference);
+        }
+    }
+
+    #[test]
+    fn test_sheet_references(){
+        let b3 = Value::CELL_REFERENCE(Coordinate{row:3, column:1}, Anchor::RELATIVE);
+        assert_eq!(Value::SHEET_REFERENCE("Sheet2".to_owned(), Box::new(b3.clone())), parse("[Sheet2!B3]").unwrap());
+        assert_eq!(Value::SHEET_REFERENCE("Bob's data!".to_owned(), Box::new(b3)), parse("['Bob''s data!'!B3]").unwrap());
+        assert_eq!(
+            Value::SHEET_REFERENCE("My Sheet".to_owned(), Box::new(Value::RANGE(Range::columns(0, 2), [Anchor::RELATIVE; 2]))),
+            parse("[ My Sheet ! A:C ]").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_anchors(){
+        let a0 = Coordinate{row:0, column:0};
+        assert_eq!(Value::CELL_REFERENCE(a0, Anchor::ABSOLUTE), parse("[$A$0]").unwrap());
+        assert_eq!(Value::CELL_REFERENCE(a0, Anchor{row: true, column: false}), parse("[A$0]").unwrap());
+        assert_eq!(Value::CELL_REFERENCE(a0, Anchor{row: false, column: true}), parse("[$a0]").unwrap());
+
+        //Each anchor stays with the row or column it was written on when the corners are swapped
+        let range = Range::cells(Coordinate{row:1, column:0}, Coordinate{row:3, column:2});
+        let anchors = [Anchor{row: false, column: true}, Anchor{row: true, column: false}];
+        assert_eq!(Value::RANGE(range, anchors), parse("[$A1:C$3]").unwrap());
+        assert_eq!(Value::RANGE(range, anchors), parse("[C$3:$A1]").unwrap());
+        let anchors = [Anchor{row: true, column: false}, Anchor{row: false, column: true}];
+        assert_eq!(Value::RANGE(range, anchors), parse("[$C$1:A3]").unwrap());
+
+        let anchors = [Anchor{row: false, column: true}, Anchor::RELATIVE];
+        assert_eq!(Value::RANGE(Range::columns(0, 2), anchors), parse("[C:$A]").unwrap());
+        let anchors = [Anchor::RELATIVE, Anchor{row: true, column: false}];
+        assert_eq!(Value::RANGE(Range::rows(3, 5), anchors), parse("[3:$5]").unwrap());
+
+        for reference in ["[$$A1]", "[A$$1]", "[A1$]", "[$]", "[$A$]", "[A:$]"] {
+            assert!(matches!(parse(reference).unwrap_err().kind, ParseErrorKind::InvalidReference(_)), "{}", reference);
+        }
+    }
+
+    #[test]
+    fn test_errors(){
+        let error = parse("ADD(1,2").unwrap_err();
+        assert_eq!(ParseErrorKind::UnexpectedEnd, error.kind);
+        assert_eq!(Span::new(7, 7), error.span);
+
+        let error = parse("ADD(1,,2)").unwrap_err();
+        assert_eq!(ParseErrorKind::UnexpectedToken("','".to_owned()), error.kind);
+        assert_eq!(Span::new(6, 7), error.span);
+
+        let error = parse("FOO(1)").unwrap_err();
+        assert_eq!(ParseErrorKind::UnknownFunction("FOO".to_owned()), error.kind);
+        assert_eq!(Span::new(0, 3), error.span);
+
+        let error = parse("ADD(1,2))").unwrap_err();
+        assert_eq!(ParseErrorKind::UnexpectedToken("')'".to_owned()), error.kind);
+        assert_eq!(Span::new(8, 9), error.span);
+
+        let error = parse("ADD([1A],2)").unwrap_err();
+        assert_eq!(ParseErrorKind::InvalidReference("1A".to_owned()), error.kind);
+        assert_eq!(Span::new(4, 8), error.span);
+
+        let error = parse("").unwrap_err();
+        assert_eq!(ParseErrorKind::UnexpectedEnd, error.kind);
+    }
+
+    #[test]
+    fn test_operators(){
+        let a1 = Value::CELL_REFERENCE(Coordinate{row:1, column:0}, Anchor::RELATIVE);
+        let b1 = Value::CELL_REFERENCE(Coordinate{row:1, column:1}, Anchor::RELATIVE);
+
+        //Multiplication binds tighter than addition
+        let expected = Value::FUNCTION(FUNCTION::ADD, vec![
+            a1.clone(),
+            Value::FUNCTION(FUNCTION::MUL, vec![b1.clone(), Value::I32(2)])
+        ]);
+        assert_eq!(expected, parse("=[A1]+[B1]*2").unwrap());
+
+        //Parentheses override precedence
+        let expected = Value::FUNCTION(FUNCTION::MUL, vec![
+            Value::FUNCTION(FUNCTION::ADD, vec![a1.clone(), b1.clone()]),
+            Value::I32(2)
+        ]);
+        assert_eq!(expected, parse("=([A1]+[B1])*2").unwrap());
+
+        //Power is right associative and binds tighter than unary minus
+        let expected = Value::FUNCTION(FUNCTION::NEG, vec![
+            Value::FUNCTION(FUNCTION::POW, vec![
+                Value::I32(2),
+                Value::FUNCTION(FUNCTION::POW, vec![Value::I32(3), Value::I32(2)])
+            ])
+        ]);
+        assert_eq!(expected, parse("=-2^3^2").unwrap());
+
+        //Subtraction is left associative
+        let expected = Value::FUNCTION(FUNCTION::SUB, vec![
+            Value::FUNCTION(FUNCTION::SUB, vec![Value::I32(5), Value::I32(2)]),
+            Value::I32(1)
+        ]);
+        assert_eq!(expected, parse("=5-2-1").unwrap());
+
+        //Comparison is the loosest, then concatenation
+        let expected = Value::FUNCTION(FUNCTION::LTE, vec![
+            Value::FUNCTION(FUNCTION::CONCAT, vec![a1.clone(), Value::STRING("x".to_owned())]),
+            Value::FUNCTION(FUNCTION::ADD, vec![b1.clone(), Value::I32(1)])
+        ]);
+        assert_eq!(expected, parse("=[A1]&\"x\"<=[B1]+1").unwrap());
+
+        assert_eq!(Value::FUNCTION(FUNCTION::NEG, vec![a1.clone()]), parse("=-[A1]").unwrap());
+        assert_eq!(Value::FUNCTION(FUNCTION::NE, vec![a1, b1]), parse("=[A1]<>[B1]").unwrap());
+    }
+
+    #[test]
+    fn test_deep_nesting(){
+        let formula = format!("{}1{}", "ADD(1,".repeat(50), ")".repeat(50));
+        let mut value = parse(&formula).unwrap();
+        let mut depth = 0;
+        while let Value::FUNCTION(FUNCTION::ADD, mut arguments) = value {
+            value = arguments.pop().unwrap();
+            depth += 1;
+        }
+        assert_eq!(50, depth);
+    }
+
+    #[test]
+    fn test_too_deeply_nested(){
+        //Thousands of levels are an error at the first level past the limit rather than running out of stack
+        let error = Value::try_from_str(&format!("={}1{}", "(".repeat(5000), ")".repeat(5000))).unwrap_err();
+        assert_eq!(ParseError::new(ParseErrorKind::TooDeeplyNested, Span::new(101, 102)), error);
+
+        let error = parse(&format!("={}1{}", "ABS(".repeat(5000), ")".repeat(5000))).unwrap_err();
+        assert_eq!(ParseError::new(ParseErrorKind::TooDeeplyNested, Span::new(401, 404)), error);
+
+        for formula in [format!("={}1", "-".repeat(5000)), format!("={}", vec!["2"; 5000].join("^"))] {
+            assert_eq!(ParseErrorKind::TooDeeplyNested, parse(&formula).unwrap_err().kind);
+        }
+
+        //Every operator of a chain is a level of the tree
+        let error = parse(&format!("={}", vec!["1"; 5000].join("+"))).unwrap_err();
+        assert_eq!(ParseError::new(ParseErrorKind::TooDeeplyNested, Span::new(1, 514)), error);
+        assert!(parse(&format!("={}", vec!["1"; 256].join("+"))).is_ok());
+    }
+}
@@ -0,0 +1,251 @@
+use crate::coordinate::{Anchor, Coordinate};
+use crate::parser::parse_coordinate;
+use crate::range::Range;
+use crate::value::Value;
+use crate::FUNCTION;
+
+//Binding strength of each level of the grammar in the parser, higher binds tighter
+const COMPARISON: u8 = 1;
+const CONCAT: u8 = 2;
+const ADDITIVE: u8 = 3;
+const TERM: u8 = 4;
+const UNARY: u8 = 5;
+const POWER: u8 = 6;
+const PRIMARY: u8 = 7;
+
+//How references and function names are written
+#[derive(PartialEq)]
+#[derive(Clone)]
+#[derive(Copy)]
+enum Notation{
+    //As parsed by this crate, [A0] is the top left cell
+    Native,
+    //As spreadsheet files store formulas, A1 is the top left cell
+    Excel
+}
+
+pub fn print(value: &Value) -> String {
+    /*
+    Writes a value back out as formula text (without the leading '='), the inverse of parse.
+    Operators are written infix with only the brackets needed to parse back into the same tree.
+     */
+    expression(value, Notation::Native)
+}
+
+pub fn print_excel(value: &Value) -> String {
+    //Writes a value as the formula text a spreadsheet file stores, with 1-based references and no brackets
+    expression(value, Notation::Excel)
+}
+
+fn expression(value: &Value, notation: Notation) -> String {
+    match value {
+        Value::FUNCTION(FUNCTION::NEG, arguments) if arguments.len() == 1 && !is_positive_number(&arguments[0]) =>
+            format!("-{}", operand(&arguments[0], UNARY, notation)),
+        Value::FUNCTION(FUNCTION::NEG, arguments) if arguments.len() == 1 && notation == Notation::Excel =>
+            format!("-({})", expression(&arguments[0], notation)),
+        Value::FUNCTION(function, arguments) if arguments.len() == 2 && operator(function).is_some() => {
+            let (symbol, level) = operator(function).unwrap();
+
+            //Power is right associative, every other operator is left associative
+            let (left, right) = if level == POWER {(PRIMARY, UNARY)} else {(level, level + 1)};
+            format!("{}{}{}", operand(&arguments[0], left, notation), symbol, operand(&arguments[1], right, notation))
+        },
+        Value::FUNCTION(function, arguments) => {
+            let name = match (function, notation) {
+                (FUNCTION::CEIL, Notation::Excel) => "CEILING".to_owned(),
+                //Functions added after the original file format are stored with a prefix
+                (FUNCTION::IFS | FUNCTION::SWITCH | FUNCTION::XOR | FUNCTION::IFNA, Notation::Excel) => format!("_xlfn.{}", function),
+                //Spreadsheets have no exact decimals, the nearest is rounding to the places or reading the number as is
+                (FUNCTION::DECIMAL, Notation::Excel) if arguments.len() == 2 => "ROUND".to_owned(),
+                (FUNCTION::DECIMAL, Notation::Excel) => "VALUE".to_owned(),
+                _ => function.to_string()
+            };
+            let arguments: Vec<String> = arguments.iter().map(|argument| expression(argument, notation)).collect();
+            format!("{}({})", name, arguments.join(","))
+        },
+        Value::STRING(text) => format!("\"{}\"", text.replace('"', "\"\"")),
+        Value::DATE(_, _) | Value::TIME(_, _) | Value::DATETIME(_, _) | Value::TIMESTAMP(_, _) => format!("\"{}\"", value.display()),
+        //Written as text so no digit is lost to a float on the way back in
+        Value::DECIMAL(_, _) if notation == Notation::Native => format!("DECIMAL(\"{}\")", value.display()),
+        Value::DECIMAL(_, _) => value.display(),
+        Value::CELL_REFERENCE(coordinate, anchor) => bracket(coordinate_text(coordinate, anchor, notation), notation),
+        Value::RANGE(range, [start_anchor, end_anchor]) => {
+            let text = match range {
+                Range::Cells(start, end) =>
+                    format!("{}:{}", coordinate_text(start, start_anchor, notation), coordinate_text(end, end_anchor, notation)),
+                Range::Columns(start, end) =>
+                    format!("{}:{}", column_text(*start, start_anchor.column), column_text(*end, end_anchor.column)),
+                Range::Rows(start, end) =>
+                    format!("{}:{}", row_text(*start, start_anchor.row, notation), row_text(*end, end_anchor.row, notation))
+            };
+            bracket(text, notation)
+        },
+        Value::SHEET_REFERENCE(sheet, reference) => match notation {
+            Notation::Native => format!("[{}!{}]", sheet_name(sheet), expression(reference, notation).trim_matches(['[', ']'])),
+            Notation::Excel => format!("{}!{}", sheet_name(sheet), expression(reference, notation))
+        },
+        other => other.to_string()
+    }
+}
+
+pub(crate) fn sheet_name(sheet: &str) -> String {
+    //Names that are not a plain word, or could be read as a cell such as B2, are quoted the same way spreadsheets do
+    let letters = sheet.chars().take_while(char::is_ascii_alphabetic).count();
+    let plain = sheet.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !sheet.starts_with(|c: char| c.is_ascii_digit())
+        && !(letters <= 3 && parse_coordinate(sheet).is_some());
+    if plain {
+        sheet.to_owned()
+    } else {
+        format!("'{}'", sheet.replace('\'', "''"))
+    }
+}
+
+fn bracket(reference: String, notation: Notation) -> String {
+    match notation {
+        Notation::Native => format!("[{}]", reference),
+        Notation::Excel => reference
+    }
+}
+
+fn coordinate_text(coordinate: &Coordinate, anchor: &Anchor, notation: Notation) -> String {
+    format!("{}{}", column_text(coordinate.column, anchor.column), row_text(coordinate.row, anchor.row, notation))
+}
+
+fn column_text(column: usize, anchored: bool) -> String {
+    format!("{}{}", if anchored {"$"} else {""}, Coordinate::column_name(column))
+}
+
+fn row_text(row: usize, anchored: bool, notation: Notation) -> String {
+    //Rows are 0-based in native notation and 1-based in Excel notation
+    let row = if notation == Notation::Excel {row + 1} else {row};
+    format!("{}{}", if anchored {"$"} else {""}, row)
+}
+
+fn operand(value: &Value, min: u8, notation: Notation) -> String {
+    //Brackets an operand which binds looser than the position it is written in
+    if precedence(value) < min {
+        format!("({})", expression(value, notation))
+    } else {
+        expression(value, notation)
+    }
+}
+
+fn precedence(value: &Value) -> u8 {
+    match value {
+        Value::FUNCTION(FUNCTION::NEG, arguments) if arguments.len() == 1 && !is_positive_number(&arguments[0]) => UNARY,
+        Value::FUNCTION(function, arguments) if arguments.len() == 2 => operator(function).map_or(PRIMARY, |(_, level)| level),
+        //A negative literal is read as a negation, so -2^2 is -(2^2) not (-2)^2
+        Value::I32(val) if *val < 0 => UNARY,
+        Value::I64(val) if *val < 0 => UNARY,
+        Value::F32(val, _) if val.is_sign_negative() => UNARY,
+        Value::F64(val, _) if val.is_sign_negative() => UNARY,
+        Value::DECIMAL(units, _) if *units < 0 => UNARY,
+        _ => PRIMARY
+    }
+}
+
+fn is_positive_number(value: &Value) -> bool {
+    /*
+    The parser folds a minus sign in front of a number into a negative literal, so negating a positive number
+    has to be written as NEG(...) to parse back as a negation
+     */
+    match value {
+        Value::I32(val) => *val >= 0,
+        Value::I64(val) => *val >= 0,
+        Value::F32(val, _) => val.is_sign_positive(),
+        Value::F64(val, _) => val.is_sign_positive(),
+        _ => false
+    }
+}
+
+fn operator(function: &FUNCTION) -> Option<(&'static str, u8)> {
+    match function {
+        FUNCTION::EQ => Some(("=", COMPARISON)),
+        FUNCTION::NE => Some(("<>", COMPARISON)),
+        FUNCTION::LT => Some(("<", COMPARISON)),
+        FUNCTION::LTE => Some(("<=", COMPARISON)),
+        FUNCTION::GT => Some((">", COMPARISON)),
+        FUNCTION::GTE => Some((">=", COMPARISON)),
+        FUNCTION::CONCAT => Some(("&", CONCAT)),
+        FUNCTION::ADD => Some(("+", ADDITIVE)),
+        FUNCTION::SUB => Some(("-", ADDITIVE)),
+        FUNCTION::MUL => Some(("*", TERM)),
+        FUNCTION::DIV => Some(("/", TERM)),
+        FUNCTION::POW => Some(("^", POWER)),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coordinate::{Anchor, Coordinate};
+    use crate::error::ErrorKind;
+    use crate::parser::parse;
+    use crate::parser::printer::{print, print_excel};
+    use crate::range::Range;
+    use crate::value::Value;
+    use crate::FUNCTION;
+
+    #[test]
+    fn test_print_operators(){
+        for formula in ["1+2*3", "(1+2)*3", "1-(2-3)", "1-2-3", "2^3^2", "(2^3)^2", "-2^2", "(-2)^2", "2^-2",
+                        "-[A1]*2", "-([A1]*2)", "1--2", "[A1]&\"x\"=\"1x\"", "1=2=0", "1=(2=0)", "NEG(2)", "--2"] {
+            assert_eq!(formula, print(&parse(formula).unwrap()))
+        }
+    }
+
+    #[test]
+    fn test_print_functions(){
+        let value = Value::FUNCTION(FUNCTION::SUM, vec![
+            Value::RANGE(Range::cells(Coordinate{row:1, column:0}, Coordinate{row:3, column:1}), [Anchor::RELATIVE; 2]),
+            Value::FUNCTION(FUNCTION::ADD, vec![Value::I32(1), Value::I32(2), Value::I32(3)]),
+            Value::STRING("say \"hi\"".to_owned()),
+            Value::F32(2.50, 2),
+            Value::ERROR(ErrorKind::REF)
+        ]);
+        assert_eq!("SUM([A1:B3],ADD(1,2,3),\"say \"\"hi\"\"\",2.50,#REF!)", print(&value));
+        assert_eq!(value, parse(&print(&value)).unwrap())
+    }
+
+    #[test]
+    fn test_print_excel(){
+        let value = parse("=CEIL(-SUM([A0:B2], [C:D], [3:4]) * -[AA9], 5) & NEG(2)").unwrap();
+        assert_eq!("CEILING(-SUM(A1:B3,C:D,4:5)*-AA10,5)&-(2)", print_excel(&value));
+
+        let value = parse("=IF(XOR([A0], [A1]), IFNA([B0], 0), IFERROR(1/[B1], 0))").unwrap();
+        assert_eq!("IF(_xlfn.XOR(A1,A2),_xlfn.IFNA(B1,0),IFERROR(1/B2,0))", print_excel(&value));
+
+        let value = parse("=SUM([Sheet2!A0:B2])+['Q1 2024'!C3]+['A1'!A0]").unwrap();
+        assert_eq!("SUM(Sheet2!A1:B3)+'Q1 2024'!C4+'A1'!A1", print_excel(&value));
+
+        let value = parse("=[$A$0]+SUM([A$1:$B2],[$C:D],[$3:$4])").unwrap();
+        assert_eq!("$A$1+SUM(A$2:$B3,$C:D,$4:$5)", print_excel(&value));
+
+        let value = Value::FUNCTION(FUNCTION::MUL, vec![parse("=DECIMAL([A0], 2)").unwrap(), Value::DECIMAL(-150, 2)]);
+        assert_eq!("ROUND(A1,2)*-1.50", print_excel(&value));
+        assert_eq!("VALUE(\"1.5\")", print_excel(&parse("=DECIMAL(\"1.5\")").unwrap()));
+    }
+
+    #[test]
+    fn test_print_decimals(){
+        //A DECIMAL is written as text for DECIMAL() to read back without going through a float
+        let value = Value::FUNCTION(FUNCTION::ADD, vec![Value::DECIMAL(1234567890123456789, 2), Value::I32(1)]);
+        assert_eq!("DECIMAL(\"12345678901234567.89\")+1", print(&value));
+        assert_eq!(print(&value), print(&parse(&print(&value)).unwrap()));
+    }
+
+    #[test]
+    fn test_print_anchors(){
+        for formula in ["[$A$0]+[A$1]*[$A1]", "SUM([$B0:C$2])", "SUM([$A:$C])+SUM([$3:4])", "[Data!$B$3]"] {
+            assert_eq!(formula, print(&parse(formula).unwrap()))
+        }
+    }
+
+    #[test]
+    fn test_print_sheet_references(){
+        for formula in ["[Sheet2!B3]", "SUM([Data!A:C])*[Data!3:4]", "['My Sheet'!A0]+['Bob''s'!A0]", "['2024'!A0]", "['B2'!A0]"] {
+            assert_eq!(formula, print(&parse(formula).unwrap()))
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::cmp::{max, min};
+use std::fmt;
+use crate::coordinate::Coordinate;
+
+//A rectangular block of cells, whole columns and whole rows extend to the edge of the data they are solved against
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub enum Range{
+    Cells(Coordinate, Coordinate),
+    Columns(usize, usize),
+    Rows(usize, usize)
+}
+
+impl Range{
+    pub fn cells(corner_1: Coordinate, corner_2: Coordinate) -> Self {
+        /*
+        Creates a range from any two opposite corners, stored as the top left and bottom right cells
+         */
+        Range::Cells(
+            Coordinate{row: min(corner_1.row, corner_2.row), column: min(corner_1.column, corner_2.column)},
+            Coordinate{row: max(corner_1.row, corner_2.row), column: max(corner_1.column, corner_2.column)}
+        )
+    }
+
+    pub fn columns(column_1: usize, column_2: usize) -> Self {
+        Range::Columns(min(column_1, column_2), max(column_1, column_2))
+    }
+
+    pub fn rows(row_1: usize, row_2: usize) -> Self {
+        Range::Rows(min(row_1, row_2), max(row_1, row_2))
+    }
+
+    pub fn bounds(&self, dim: (usize, usize)) -> (Coordinate, Coordinate) {
+        /*
+        Returns the top left and bottom right (inclusive) cells of the range for data of the given (rows, columns) shape.
+        Whole columns or rows span the full data, cell ranges are returned as written even if they go past the data.
+         */
+        let (rows, columns) = dim;
+        match *self {
+            Range::Cells(start, end) => (start, end),
+            Range::Columns(start, end) => (
+                Coordinate{row: 0, column: start},
+                Coordinate{row: rows.saturating_sub(1), column: end}
+            ),
+            Range::Rows(start, end) => (
+                Coordinate{row: start, column: 0},
+                Coordinate{row: end, column: columns.saturating_sub(1)}
+            )
+        }
+    }
+
+    pub fn shape(&self, dim: (usize, usize)) -> (usize, usize) {
+        /*
+        Number of (rows, columns) of the range inside data of the given shape. The part past the edge of the data is
+        left out, it could only hold empty cells and a range may be written to reach as far as usize::MAX.
+         */
+        let (start, end) = self.bounds(dim);
+        (
+            dim.0.min(end.row.saturating_add(1)).saturating_sub(start.row),
+            dim.1.min(end.column.saturating_add(1)).saturating_sub(start.column)
+        )
+    }
+}
+
+//Formatting for Range, matches the syntax used inside a cell reference
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Range::Cells(start, end) => write!(f, "{}:{}", start, end),
+            Range::Columns(start, end) => write!(f, "{}:{}", Coordinate::column_name(*start), Coordinate::column_name(*end)),
+            Range::Rows(start, end) => write!(f, "{}:{}", start, end)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coordinate::Coordinate;
+    use crate::range::Range;
+
+    #[test]
+    fn test_corners_are_ordered(){
+        assert_eq!(
+            Range::Cells(Coordinate{row:1, column:0}, Coordinate{row:10, column:2}),
+            Range::cells(Coordinate{row:10, column:0}, Coordinate{row:1, column:2})
+        );
+        assert_eq!(Range::Columns(0, 3), Range::columns(3, 0));
+    }
+
+    #[test]
+    fn test_bounds(){
+        assert_eq!(
+            (Coordinate{row:0, column:1}, Coordinate{row:4, column:1}),
+            Range::columns(1, 1).bounds((5, 3))
+        );
+        assert_eq!(
+            (Coordinate{row:3, column:0}, Coordinate{row:3, column:2}),
+            Range::rows(3, 3).bounds((5, 3))
+        );
+        assert_eq!((1, 3), Range::rows(3, 3).shape((5, 3)));
+        assert_eq!((0, 0), Range::columns(1, 1).shape((0, 0)));
+    }
+
+    #[test]
+    fn test_shape_is_cut_to_the_data(){
+        assert_eq!((1, 1), Range::cells(Coordinate{row:0, column:0}, Coordinate{row:100000000, column:0}).shape((1, 1)));
+        assert_eq!((1, 2), Range::cells(Coordinate{row:0, column:0}, Coordinate{row:usize::MAX, column:usize::MAX}).shape((1, 2)));
+        assert_eq!((0, 0), Range::cells(Coordinate{row:5, column:5}, Coordinate{row:usize::MAX, column:6}).shape((1, 2)));
+        assert_eq!((0, 3), Range::rows(usize::MAX, usize::MAX).shape((5, 3)));
+    }
+
+    #[test]
+    fn test_display(){
+        assert_eq!("A1:C10", Range::cells(Coordinate{row:1, column:0}, Coordinate{row:10, column:2}).to_string());
+        assert_eq!("A:B", Range::columns(0, 1).to_string());
+        assert_eq!("3:3", Range::rows(3, 3).to_string());
+    }
+}
@@ -0,0 +1,260 @@
+use ndarray::{concatenate, Array2, Axis};
+use crate::coordinate::Coordinate;
+use crate::error::ErrorKind;
+use crate::io::MAX_CELLS;
+use crate::range::Range;
+use crate::value::Value;
+use crate::workbook::same_sheet;
+use crate::DataFrame;
+
+//Rows (Axis(0)) or columns (Axis(1)) inserted or deleted at an index, everything after them moves along by count
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub(crate) struct Shift{
+    axis: Axis,
+    at: usize,
+    count: usize,
+    delete: bool
+}
+
+impl Shift {
+    pub(crate) fn insert(axis: Axis, at: usize, count: usize) -> Self {
+        Shift{axis, at, count, delete: false}
+    }
+
+    pub(crate) fn delete(axis: Axis, at: usize, count: usize) -> Self {
+        Shift{axis, at, count, delete: true}
+    }
+
+    pub(crate) fn check(&self, dim: (usize, usize)) -> Result<(), String> {
+        /*
+        Rows and columns can be inserted anywhere up to the end as long as the sheet stays within MAX_CELLS, only
+        existing ones can be deleted
+         */
+        let (length, other, name) = if self.axis == Axis(0) {(dim.0, dim.1, "rows")} else {(dim.1, dim.0, "columns")};
+        let cells = length.checked_add(self.count).and_then(|length| length.checked_mul(other.max(1)));
+        match (self.delete, self.at.checked_add(self.count)) {
+            (false, _) if self.at > length => Err(format!("Can not insert {} at {}, there are only {} {}", name, self.at, length, name)),
+            (false, _) if cells.is_none_or(|cells| cells > MAX_CELLS) => Err(format!(
+                "Can not insert {} {}, a sheet can have at most {} cells", self.count, name, MAX_CELLS
+            )),
+            (true, Some(end)) if end > length => Err(format!(
+                "Can not delete {} {} to {}, there are only {} {}", name, self.at, end - 1, length, name
+            )),
+            (true, None) => Err(format!("Can not delete {} {} from {}, there are only {} {}", self.count, name, self.at, length, name)),
+            _ => Ok(())
+        }
+    }
+
+    pub(crate) fn apply(&self, data: &Array2<Value>) -> Array2<Value> {
+        //Moves the cells of data, inserted cells are empty
+        let after = self.at + if self.delete {self.count} else {0};
+        let mut shape = data.raw_dim();
+        shape[self.axis.index()] = if self.delete {0} else {self.count};
+        let inserted = Array2::<Value>::default(shape);
+        concatenate(self.axis, &[
+            data.slice_axis(self.axis, (..self.at).into()),
+            inserted.view(),
+            data.slice_axis(self.axis, (after..).into())
+        ]).expect("the pieces of the same array always line up")
+    }
+
+    pub(crate) fn rewrite(&self, value: &Value, local: bool, sheet: Option<&str>) -> Value {
+        /*
+        Moves the references of a formula to follow the cells they point at. References on this sheet are moved when
+        local is set, references to other sheets when they name sheet. References to deleted cells become #REF!
+         */
+        match value {
+            Value::CELL_REFERENCE(coordinate, anchor) if local => match self.coordinate(*coordinate) {
+                Some(moved) => Value::CELL_REFERENCE(moved, *anchor),
+                None => Value::ERROR(ErrorKind::REF)
+            },
+            Value::RANGE(range, anchors) if local => match self.range(*range) {
+                Some(moved) => Value::RANGE(moved, *anchors),
+                None => Value::ERROR(ErrorKind::REF)
+            },
+            Value::SHEET_REFERENCE(name, reference) if sheet.is_some_and(|sheet| same_sheet(name, sheet)) =>
+                match self.rewrite(reference, true, None) {
+                    Value::ERROR(kind) => Value::ERROR(kind),
+                    moved => Value::SHEET_REFERENCE(name.to_owned(), Box::new(moved))
+                },
+            Value::FUNCTION(function, values) =>
+                Value::FUNCTION(function.clone(), values.iter().map(|v| self.rewrite(v, local, sheet)).collect()),
+            other => other.clone()
+        }
+    }
+
+    fn index(&self, index: usize) -> Option<usize> {
+        //Where a row or column ends up, None when it was deleted or would be moved past the largest index
+        match self.delete {
+            _ if index < self.at => Some(index),
+            false => index.checked_add(self.count),
+            true if index - self.at < self.count => None,
+            true => Some(index - self.count)
+        }
+    }
+
+    fn span(&self, start: usize, end: usize) -> Option<(usize, usize)> {
+        /*
+        Where the rows or columns start to end (inclusive) of a range end up. Inserting inside a range grows it and
+        deleting part of one shrinks it, the range is only lost when every row or column of it is deleted.
+         */
+        if !self.delete {
+            return Some((self.index(start)?, self.index(end)?))
+        }
+        if start >= self.at && end - self.at < self.count {
+            return None
+        }
+        let start = self.index(start).unwrap_or(self.at);
+        let end = self.index(end).unwrap_or(self.at.saturating_sub(1));
+        Some((start, end))
+    }
+
+    fn coordinate(&self, coordinate: Coordinate) -> Option<Coordinate> {
+        match self.axis {
+            Axis(0) => Some(Coordinate{row: self.index(coordinate.row)?, column: coordinate.column}),
+            _ => Some(Coordinate{row: coordinate.row, column: self.index(coordinate.column)?})
+        }
+    }
+
+    fn range(&self, range: Range) -> Option<Range> {
+        match (range, self.axis) {
+            (Range::Cells(start, end), Axis(0)) => self.span(start.row, end.row).map(|(first, last)| Range::Cells(
+                Coordinate{row: first, column: start.column}, Coordinate{row: last, column: end.column}
+            )),
+            (Range::Cells(start, end), _) => self.span(start.column, end.column).map(|(first, last)| Range::Cells(
+                Coordinate{row: start.row, column: first}, Coordinate{row: end.row, column: last}
+            )),
+            (Range::Rows(start, end), Axis(0)) => self.span(start, end).map(|(first, last)| Range::Rows(first, last)),
+            (Range::Columns(start, end), Axis(1)) => self.span(start, end).map(|(first, last)| Range::Columns(first, last)),
+            //Whole rows are not affected by columns moving and whole columns by rows moving
+            (range, _) => Some(range)
+        }
+    }
+}
+
+impl DataFrame {
+    pub fn insert_rows(&mut self, at: usize, count: usize) -> Result<(), String> {
+        //Inserts empty rows before row at, at can be the number of rows to add them to the end
+        self.shift(Shift::insert(Axis(0), at, count))
+    }
+
+    pub fn delete_rows(&mut self, at: usize, count: usize) -> Result<(), String> {
+        self.shift(Shift::delete(Axis(0), at, count))
+    }
+
+    pub fn insert_columns(&mut self, at: usize, count: usize) -> Result<(), String> {
+        //Inserts empty columns before column at, at can be the number of columns to add them to the end
+        self.shift(Shift::insert(Axis(1), at, count))
+    }
+
+    pub fn delete_columns(&mut self, at: usize, count: usize) -> Result<(), String> {
+        self.shift(Shift::delete(Axis(1), at, count))
+    }
+
+    fn shift(&mut self, shift: Shift) -> Result<(), String> {
+        /*
+        Moves the cells then points every reference at the cells they referenced before, and solves every cell again
+         */
+        shift.check(self.data.dim())?;
+        self.move_cells(shift, None);
+        self.rebuild();
+        Ok(())
+    }
+
+    pub(crate) fn move_cells(&mut self, shift: Shift, sheet: Option<&str>) {
+        //Moves the cells without solving them again, references to the named sheet are moved as well
+        self.data = shift.apply(&self.data).map(|value| shift.rewrite(value, true, sheet));
+        //Monetary columns move with their cells and are dropped when deleted
+        if shift.axis == Axis(1) {
+            self.monetary = self.monetary.iter().filter_map(|(column, places)| Some((shift.index(*column)?, *places))).collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::test_helpers::{coordinate, raw, sheet};
+    use crate::value::Value;
+
+    #[test]
+    fn test_insert_rows(){
+        let mut input = sheet(&[
+            &["1", "=SUM([A0:A2])"],
+            &["2", "=[A1]*[A:A]"],
+            &["3", "=SUM([1:2])"]
+        ]);
+        input.insert_rows(1, 2).unwrap();
+
+        assert_eq!((5, 2), input.dim());
+        assert_eq!(Some(&Value::NULL()), input.raw(coordinate(1, 0)));
+        assert_eq!("=SUM([A0:A4])", raw(&input, 0, 1));
+        assert_eq!("=[A3]*[A:A]", raw(&input, 3, 1));
+        assert_eq!("=SUM([3:4])", raw(&input, 4, 1));
+        assert_eq!(Some(&Value::I32(6)), input.value(coordinate(0, 1)));
+
+        //New cells are part of the ranges they were inserted into
+        input.set_cell(coordinate(2, 0), Value::I32(10)).unwrap();
+        assert_eq!(Some(&Value::I32(16)), input.value(coordinate(0, 1)));
+
+        //Including at the end
+        input.insert_rows(5, 1).unwrap();
+        assert_eq!((6, 2), input.dim());
+        assert!(input.insert_rows(7, 1).is_err());
+    }
+
+    #[test]
+    fn test_delete_rows(){
+        let mut input = sheet(&[
+            &["1", "=SUM([A0:A3])"],
+            &["2", "=[A2]+[A3]"],
+            &["3", "=SUM([A1:A2])"],
+            &["4", "=[B1]"]
+        ]);
+        input.delete_rows(1, 2).unwrap();
+
+        assert_eq!((2, 2), input.dim());
+        assert_eq!("=SUM([A0:A1])", raw(&input, 0, 1));
+        assert_eq!("#REF!", raw(&input, 1, 1));
+        assert_eq!(Some(&Value::I32(5)), input.value(coordinate(0, 1)));
+
+        let mut input = sheet(&[&["1", "=[A1]+[A2]", "=SUM([A1:A2])"], &["2", "", ""], &["3", "", ""]]);
+        input.delete_rows(1, 1).unwrap();
+        assert_eq!("=#REF!+[A1]", raw(&input, 0, 1));
+        assert_eq!("=SUM([A1:A1])", raw(&input, 0, 2));
+        assert_eq!(Some(&Value::I32(3)), input.value(coordinate(0, 2)));
+
+        assert!(input.delete_rows(1, 2).is_err());
+    }
+
+    #[test]
+    fn test_limits(){
+        let mut input = sheet(&[&["1", "=[A18446744073709551615]"], &["2", ""], &["3", ""]]);
+        assert_eq!(
+            Err("Can not delete 18446744073709551615 rows from 1, there are only 3 rows".to_owned()),
+            input.delete_rows(1, usize::MAX)
+        );
+        assert!(input.insert_rows(0, usize::MAX).is_err());
+        assert!(input.insert_columns(0, 1_000_000).is_err());
+        assert_eq!((3, 2), input.dim());
+
+        //A reference moved past the largest row no longer points at anything
+        input.insert_rows(0, 1).unwrap();
+        assert_eq!("#REF!", raw(&input, 1, 1));
+    }
+
+    #[test]
+    fn test_columns(){
+        let mut input = sheet(&[&["1", "2", "3", "=SUM([A0:C0])+[C0]", "=SUM([B:C])+SUM([1:1])"]]);
+        input.insert_columns(1, 1).unwrap();
+        assert_eq!("=SUM([A0:D0])+[D0]", raw(&input, 0, 4));
+        assert_eq!("=SUM([C:D])+SUM([1:1])", raw(&input, 0, 5));
+
+        input.delete_columns(2, 2).unwrap();
+        assert_eq!("=SUM([A0:B0])+#REF!", raw(&input, 0, 2));
+        assert_eq!("=SUM(#REF!)+SUM([1:1])", raw(&input, 0, 3));
+        assert_eq!(Some(&Value::ERROR(ErrorKind::REF)), input.value(coordinate(0, 2)));
+    }
+}
@@ -0,0 +1,69 @@
+use std::fmt;
+use ndarray::Array2;
+use crate::coordinate::Coordinate;
+use crate::value::Value;
+
+//Cells wider than this are cut short when a DataFrame is printed, unless set otherwise
+pub const DEFAULT_MAX_WIDTH: usize = 20;
+
+pub(crate) fn write_table(f: &mut fmt::Formatter, values: &Array2<Value>, max_width: usize) -> fmt::Result {
+    /*
+    Writes values as a grid with the column letters along the top and row numbers down the side.
+    Numbers are right aligned, everything else left aligned, and cells longer than max_width end in '…'.
+     */
+    let (rows, columns) = values.dim();
+    let text = values.map(|value| truncate(value.display(), max_width));
+
+    let label_width = rows.saturating_sub(1).to_string().len();
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| text.column(column).iter()
+            .map(|cell| cell.chars().count())
+            .chain([Coordinate::column_name(column).len()])
+            .max()
+            .unwrap_or(0))
+        .collect();
+
+    let header: Vec<String> = widths.iter().enumerate()
+        .map(|(column, width)| format!("{:<width$}", Coordinate::column_name(column), width=width))
+        .collect();
+    write_line(f, &format!("{:label_width$} | {}", "", header.join(" | "), label_width=label_width))?;
+
+    let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    write_line(f, &format!("{}-+-{}", "-".repeat(label_width), rule.join("-+-")))?;
+
+    for row in 0..rows {
+        let cells: Vec<String> = widths.iter().enumerate()
+            .map(|(column, width)| match &values[(row, column)] {
+                value if value.is_number() => format!("{:>width$}", text[(row, column)], width=width),
+                _ => format!("{:<width$}", text[(row, column)], width=width)
+            })
+            .collect();
+        write_line(f, &format!("{:>label_width$} | {}", row, cells.join(" | "), label_width=label_width))?;
+    }
+    Ok(())
+}
+
+fn write_line(f: &mut fmt::Formatter, line: &str) -> fmt::Result {
+    writeln!(f, "{}", line.trim_end())
+}
+
+fn truncate(text: String, max_width: usize) -> String {
+    if text.chars().count() <= max_width {
+        return text
+    }
+    let mut cut: String = text.chars().take(max_width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::table::truncate;
+
+    #[test]
+    fn test_truncate(){
+        assert_eq!("abc", truncate("abc".to_owned(), 3));
+        assert_eq!("ab…", truncate("abcd".to_owned(), 3));
+        assert_eq!("…", truncate("abcd".to_owned(), 1));
+    }
+}
@@ -0,0 +1,17 @@
+use crate::coordinate::Coordinate;
+use crate::DataFrame;
+
+//Shorthands shared by the tests of the modules working on a whole DataFrame
+
+pub(crate) fn coordinate(row: usize, column: usize) -> Coordinate {
+    Coordinate{row, column}
+}
+
+pub(crate) fn raw(df: &DataFrame, row: usize, column: usize) -> String {
+    //The cell as entered, formulas are not solved
+    df.raw(coordinate(row, column)).unwrap().to_string()
+}
+
+pub(crate) fn sheet(rows: &[&[&str]]) -> DataFrame {
+    DataFrame::new(rows.iter().map(|row| row.iter().map(|cell| cell.to_string()).collect()).collect())
+}
@@ -1,11 +1,43 @@
 use std::fmt;
+use std::sync::LazyLock;
 use regex::Regex;
-use chrono::{NaiveDateTime,};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
 use ndarray::Array2;
-use qndr::{get_alphabets, get_numbers};
-use crate::coordinate::Coordinate;
+use crate::error::ErrorKind;
+use crate::coordinate::{Anchor, Coordinate};
+use crate::datetime::DateParsing;
+use crate::range::Range;
 use crate::FUNCTION;
+use std::cmp::Ordering;
+use crate::functions::abs::abs;
 use crate::functions::add::add;
+use crate::functions::average::average;
+use crate::functions::ceil::ceil;
+use crate::functions::compare::compare;
+use crate::functions::concat::concat;
+use crate::functions::count::{count, counta};
+use crate::functions::decimal;
+use crate::functions::decimal::to_decimal;
+use crate::functions::div::div;
+use crate::functions::floor::floor;
+use crate::functions::logical::{is_logical, logical};
+use crate::functions::max::max;
+use crate::functions::min::min;
+use crate::functions::modulo::modulo;
+use crate::functions::mul::mul;
+use crate::functions::neg::neg;
+use crate::functions::pow::pow;
+use crate::functions::product::product;
+use crate::functions::round::round;
+use crate::functions::sqrt::sqrt;
+use crate::functions::sub::sub;
+use crate::functions::sum::sum;
+use crate::parser;
+use crate::parser::{ParseError, ParseErrorKind};
+use crate::workbook::same_sheet;
+
+//The point and decimal places of a number, e.g. .50 in 1234.50
+static DECIMAL_PLACES: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\.(\d*)").unwrap());
 
 #[allow(clippy::upper_case_acronyms)]
 #[allow(non_camel_case_types)]
@@ -15,118 +47,239 @@ use crate::functions::add::add;
 pub enum Value{
     NULL(),
     I32(i32),
+    I64(i64),
     F32(f32, usize),
+    F64(f64, usize),
+    //An exact amount of units of 10^-places, such as cents for money with 2 decimal places
+    DECIMAL(i128, usize),
     STRING(String),
+    BOOLEAN(bool),
+    //Dates and times keep the chrono format they are shown in
+    DATE(NaiveDate, String),
+    TIME(NaiveTime, String),
     DATETIME(NaiveDateTime, String),
+    //A moment at a fixed offset from UTC, such as 2021-04-18T21:03:28+10:00
+    TIMESTAMP(DateTime<FixedOffset>, String),
     FUNCTION(FUNCTION, Vec<Value>),
-    CELL_REFERENCE(Coordinate)
+    CELL_REFERENCE(Coordinate, Anchor),
+    //The anchors of the top left and bottom right corners
+    RANGE(Range, [Anchor; 2]),
+    //A CELL_REFERENCE or RANGE on the named sheet of a workbook
+    SHEET_REFERENCE(String, Box<Value>),
+    ARRAY(Array2<Value>),
+    ERROR(ErrorKind)
 }
 
 //Implementation for default Values of each datatype. Mainly used for comparisons between Enum variants
 impl Value{
-    fn null_default() -> Self {Value::NULL()}
     fn i32_default() -> Self {Value::I32(0)}
+    fn i64_default() -> Self {Value::I64(0)}
     fn f32_default() -> Self {Value::F32(0.0, 0)}
-    fn string_default() -> Self {Value::STRING("".to_owned())}
-    fn function_default() -> Self{Value::FUNCTION(FUNCTION::ADD, vec![Value::I32(1), Value::I32(1)])}
-    fn reference_default() -> Self{Value::CELL_REFERENCE(Coordinate{row:0,column:0})}
+    fn f64_default() -> Self {Value::F64(0.0, 0)}
+    fn decimal_default() -> Self {Value::DECIMAL(0, 0)}
 
     pub fn create_from_str(value: String) -> Value {
+        //A formula which does not parse is kept as the text that was typed
+        Value::try_from_str(&value).unwrap_or(Value::STRING(value))
+    }
+
+    pub fn try_from_str(value: &str) -> Result<Value, ParseError> {
+        //Parses user input with every date format, see try_from_str_with
+        Value::try_from_str_with(value, &DateParsing::default())
+    }
+
+    pub fn try_from_str_with(value: &str, dates: &DateParsing) -> Result<Value, ParseError> {
+        /*
+        Parses user input into a Value, reading dates and times with the given formats. Fails only when the input was
+        explicitly made a formula with a leading '=' but is not valid formula syntax.
+         */
 
         //If input is ""
-        if value == "".to_owned(){
-            return Value::NULL()
+        if value.is_empty() {
+            return Ok(Value::NULL())
+        }
+
+        //A leading apostrophe forces the rest of the input to be text, e.g. '123 or '=A1
+        if let Some(text) = value.strip_prefix('\'') {
+            return Ok(Value::STRING(text.to_owned()))
+        }
+
+        //If input is a date, time or both in one of the accepted formats
+        if let Some(res) = dates.parse(value) {
+            return Ok(res)
         }
 
-        //If input is datetime
-        //Currently only parses a single format
-        const DATETIME_FORMAT: &str = "%Y%m%d %H%M%S";
-        if let Ok(res) = NaiveDateTime::parse_from_str(&value, DATETIME_FORMAT){
-            return Value::DATETIME(res, DATETIME_FORMAT.to_owned());
+        //If input is a number
+        if let Some(res) = Value::from_number_str(value) {
+            return Ok(res)
         }
 
-        //If input is i32
-        if let Ok(res) = value.parse::<i32>() {
-            return Value::I32(res)
+        //If input is TRUE or FALSE in any case
+        if let Some(res) = Value::from_boolean_str(value.trim()) {
+            return Ok(res)
         }
 
-        //If input is f32
-        if let Ok(res) = value.parse::<f32>() {
-            let re = Regex::new(r"\.(\d*)").unwrap();
-            let precision = re.find(&value).map(|x| x.len()-1).unwrap_or(1);   //Minus 1 since capture includes the decimal point
-            return Value::F32(res, precision)
+        //If input is an error code such as #DIV/0!
+        if let Some(kind) = ErrorKind::from_code(value.trim()) {
+            return Ok(Value::ERROR(kind))
         }
 
-        //Check if is a Function string - String can represent other functions as this solves for recursive methods
-        if let Ok(res) = FUNCTION::starts_with(&value){
-
-            //Split on the opening (
-            let mut splitter_opening = value.splitn(2, '(');
-            let mut values = splitter_opening.nth(1).unwrap().trim();
-
-            //Split on the closing )
-            let mut splitter_closing = values.splitn(2, ')');
-            values = splitter_closing.next().unwrap().trim();
-
-            // Split on all , not inside (). This is done manually as rust regex does not support negative lookahead
-            let mut brackets_count = 0;
-            let mut end_last_string_index = 0;
-            let mut split_values: Vec<Value> = vec![];
-            for (i, c) in values.chars().enumerate(){
-
-                //Add a count if a bracket was opened
-                if c == '(' {brackets_count+=1}
-                //Minus a count if a bracket was closed
-                if c == ')' {brackets_count-=1}
-
-                //Add any values to vec if can split as not inside ()
-                if c == ',' && brackets_count == 0{
-                    split_values.push(Value::create_from_str(values[end_last_string_index..i].to_owned()));
-                    end_last_string_index = i+1 //Increment by 1 so next split does not contain the comma just split on
-                }
-
-                //If the last char then add anything else to the vec
-                //Can do this as the string should never end in a comma so should not duplicate by accident
-                if i == values.len()-1{
-                    split_values.push(Value::create_from_str(values[end_last_string_index..].to_owned()));
-                }
+        //If input is a formula, either explicitly started with '=' or a bare function call/cell reference
+        //A formula using a name that does not exist is a #NAME? error, any other input which only looks
+        //like a formula but does not parse is kept as a string
+        if Value::is_formula(value) {
+            let explicit = value.trim_start().starts_with('=');
+            match parser::parse(value) {
+                //A DECIMAL is written as =DECIMAL("1200.50"), which reads back as the same DECIMAL rather than a formula
+                Ok(Value::FUNCTION(FUNCTION::DECIMAL, arguments)) if matches!(arguments.as_slice(), [Value::STRING(_)]) =>
+                    return Ok(decimal::decimal(arguments[0].clone(), Value::NULL()).unwrap_or(Value::FUNCTION(FUNCTION::DECIMAL, arguments))),
+                Ok(res) => return Ok(res),
+                Err(ParseError{kind: ParseErrorKind::UnknownFunction(_) | ParseErrorKind::UnknownName(_), ..})
+                    if explicit => return Ok(Value::ERROR(ErrorKind::NAME)),
+                Err(error) if explicit => return Err(error),
+                Err(_) => {}
             }
+        }
+
+        //If all checks are done assume its an actual string
+        Ok(Value::STRING(value.to_owned()))
+    }
 
-            return Value::FUNCTION(res, split_values)
+    pub(crate) fn from_number_str(value: &str) -> Option<Value> {
+        /*
+        Parses a numeric string into the narrowest type holding it without loss, I32 then I64 for whole numbers and
+        F32 then F64 otherwise, tracking the number of decimal places as the precision
+         */
+
+        //If input is a whole number
+        if let Ok(res) = value.parse::<i64>() {
+            return Some(Value::integer(res))
         }
 
-        //If input is a cell reference
-        let re = Regex::new(r"^\[{1}\w*\]{1}$").unwrap();
-        if let Some(res) = re.find(&value.to_uppercase()) {
+        //If input is a float
+        if let Ok(res) = value.parse::<f64>() {
+            //Rust also parses words such as "inf" and "NaN", they are not numbers in a cell
+            if !res.is_finite() {
+                return None
+            }
+            //A number without a point, such as one too large for an i64, has no decimal places to show
+            let precision = DECIMAL_PLACES.find(value).map_or(0, |x| x.len()-1);   //Minus 1 since capture includes the decimal point
+            return Some(Value::float(res, precision))
+        }
 
-            //Find the letters to convert to column number
-            let column: i32 = get_alphabets(res.as_str()).chars()
-                .rev()
-                .enumerate()
-                .map(|(i,c)| i32::pow(26,i as u32)*(c as i32 - 64))
-                .sum::<i32>()-1; //Minus 1 as 0 indexing
+        None
+    }
 
-            //Find the digits to convert to row number - can unwrap as we only got numeric chars
-            let row = get_numbers(res.as_str()).parse::<i32>().unwrap();
+    pub(crate) fn integer(val: i64) -> Value {
+        //An I32 when the number fits in one, otherwise an I64
+        i32::try_from(val).map_or(Value::I64(val), Value::I32)
+    }
+
+    pub(crate) fn float(val: f64, precision: usize) -> Value {
+        //An F32 when it shows the same to precision decimal places, otherwise an F64
+        Value::promote(val as f32, val, precision)
+    }
 
-            return Value::CELL_REFERENCE(Coordinate{row:row as usize, column:column as usize})
+    pub(crate) fn promote(narrow: f32, wide: f64, precision: usize) -> Value {
+        /*
+        The result of an F32 calculation, kept as the F32 result narrow when it shows the same as the same calculation
+        done as an f64 to precision decimal places, otherwise the F64 result wide so no digits are lost
+         */
+        if narrow.is_finite() && format!("{:.prec$}", narrow, prec=precision) == format!("{:.prec$}", wide, prec=precision) {
+            Value::F32(narrow, precision)
+        } else {
+            Value::F64(wide, precision)
         }
+    }
 
-        //If all checks are done assume its an actual string
-        return Value::STRING(value)
+    pub(crate) fn computed(narrow: f32, wide: f64) -> Value {
+        //The same as promote for a result with no precision of its own, such as a division, shown to the places it needs
+        Value::promote(narrow, wide, fitted_precision(wide))
+    }
+
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        //The value of any number as an f64, None for anything else
+        match self {
+            Value::I32(val) => Some(*val as f64),
+            Value::I64(val) => Some(*val as f64),
+            Value::F32(val, _) => Some(widen(*val)),
+            Value::F64(val, _) => Some(*val),
+            Value::DECIMAL(units, places) => Some(*units as f64 / 10_f64.powi(*places as i32)),
+            _ => None
+        }
+    }
+
+    pub(crate) fn is_number(&self) -> bool {
+        matches!(self, Value::I32(_) | Value::I64(_) | Value::F32(_, _) | Value::F64(_, _) | Value::DECIMAL(_, _))
+    }
+
+    pub(crate) fn from_boolean_str(value: &str) -> Option<Value> {
+        match value.to_uppercase().as_str() {
+            "TRUE" => Some(Value::BOOLEAN(true)),
+            "FALSE" => Some(Value::BOOLEAN(false)),
+            _ => None
+        }
     }
 
-    pub fn solve_reference<'a>(&'a self, data: &'a Array2<Value>) -> Option<&Value>{
+    fn is_formula(value: &str) -> bool {
+        let trimmed = value.trim_start();
+        if trimmed.starts_with('=') || trimmed.starts_with('[') {
+            return true
+        }
+
+        //Text before the first '(' has to be a known function name
+        match trimmed.split_once('(') {
+            Some((name, _)) => FUNCTION::from_name(name.trim()).is_some(),
+            None => false
+        }
+    }
+
+    pub fn precedents(&self, dim: (usize, usize)) -> Vec<Coordinate> {
         /*
-        Returns the value in a given cell for the referenced cell.
+        Returns every cell within data of the given (rows, columns) shape that this value references
          */
+        match self {
+            Value::CELL_REFERENCE(coord, _) if coord.row < dim.0 && coord.column < dim.1 => vec![*coord],
+            Value::RANGE(range, _) => {
+                let (start, end) = range.bounds(dim);
+                (start.row..=end.row.min(dim.0.saturating_sub(1)))
+                    .flat_map(|row| (start.column..=end.column.min(dim.1.saturating_sub(1))).map(move |column| Coordinate{row, column}))
+                    .filter(|coord| coord.row < dim.0 && coord.column < dim.1)
+                    .collect()
+            },
+            Value::FUNCTION(_, values) => values.iter().flat_map(|v| v.precedents(dim)).collect(),
+            _ => vec![]
+        }
+    }
 
-        //Dont need the if, but cant find how to resolve without if let or match statement
-        if let Self::CELL_REFERENCE(coord) = self {
-            return data.get((coord.row, coord.column))
+    pub fn sheet_references(&self) -> Vec<(&str, &Value)> {
+        /*
+        Returns the sheet name and the reference made on it for every reference to another sheet
+         */
+        match self {
+            Value::SHEET_REFERENCE(sheet, reference) => vec![(sheet.as_str(), reference.as_ref())],
+            Value::FUNCTION(_, values) => values.iter().flat_map(|v| v.sheet_references()).collect(),
+            _ => vec![]
         }
+    }
 
-        return None
+    pub(crate) fn rename_sheet(&mut self, old: &str, new: &str) {
+        //Points every reference to the old sheet name at the new one
+        match self {
+            Value::SHEET_REFERENCE(sheet, _) if same_sheet(sheet, old) => *sheet = new.to_owned(),
+            Value::FUNCTION(_, values) => values.iter_mut().for_each(|v| v.rename_sheet(old, new)),
+            _ => {}
+        }
+    }
+
+    pub fn solve_reference<'a>(&self, data: &'a Array2<Value>) -> Option<&'a Value>{
+        /*
+        Returns the value in a given cell for the referenced cell.
+         */
+        match self {
+            Self::CELL_REFERENCE(coord, _) => data.get((coord.row, coord.column)),
+            _ => None
+        }
     }
 }
 
@@ -137,68 +290,247 @@ impl Default for Value {
 //Formatting for Value Enum
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        /*
+        Writes the value as the text typed into a cell to create it, so create_from_str(value.to_string()) gives back
+        the same value. Use display for the text shown in a cell.
+         */
         match self {
             Value::NULL() => write!(f, ""),
-            Value::I32(val) => write!(f, "{}", val.to_string()),
-            Value::F32(val, precision) => write!(f, "{}", format!("{val:.prec$}", val=val, prec=precision)),
+            Value::I32(val) => write!(f, "{}", val),
+            Value::I64(val) => write!(f, "{}", val),
+            //A trailing point keeps a whole number with no decimal places a float when read back
+            Value::F32(val, 0) => write!(f, "{:.0}.", val),
+            Value::F32(val, precision) => write!(f, "{val:.prec$}", val=val, prec=precision),
+            Value::F64(val, 0) => write!(f, "{:.0}.", val),
+            Value::F64(val, precision) => write!(f, "{val:.prec$}", val=val, prec=precision),
+            //Plain text such as 1200.50 would be read back as a float
+            Value::DECIMAL(_, _) => write!(f, "={}", parser::printer::print(self)),
+            Value::STRING(val) if Value::is_ambiguous(val) => write!(f, "'{}", val),
             Value::STRING(val) => write!(f, "{}", val),
+            Value::BOOLEAN(val) => write!(f, "{}", if *val {"TRUE"} else {"FALSE"}),
+            Value::DATE(val, format) => write!(f, "{}", val.format(format)),
+            Value::TIME(val, format) => write!(f, "{}", val.format(format)),
             Value::DATETIME(val, format) => write!(f, "{}", val.format(format)),
-            Value::FUNCTION(function, vals) => write!(f, "{}", "Not Implemented"),
-            Value::CELL_REFERENCE(coords) => write!(f, "{}", coords)
+            Value::TIMESTAMP(val, format) => write!(f, "{}", val.format(format)),
+            Value::FUNCTION(_, _) | Value::CELL_REFERENCE(_, _) | Value::RANGE(_, _) | Value::SHEET_REFERENCE(_, _) =>
+                write!(f, "={}", parser::printer::print(self)),
+            Value::ERROR(kind) => write!(f, "{}", kind),
+            Value::ARRAY(values) => write!(f, "{}", Value::array_text(values, Value::to_string))
         }
     }
 }
 
+impl Value {
+    pub fn display(&self) -> String {
+        /*
+        The text shown in a cell for a solved value. Text is shown as is and numbers to their precision.
+         */
+        match self {
+            Value::F32(val, precision) => format!("{val:.prec$}", val=val, prec=precision),
+            Value::F64(val, precision) => format!("{val:.prec$}", val=val, prec=precision),
+            Value::DECIMAL(units, places) => decimal::text(*units, *places),
+            Value::STRING(val) => val.to_owned(),
+            Value::ARRAY(values) => Value::array_text(values, Value::display),
+            other => other.to_string()
+        }
+    }
+
+    fn is_ambiguous(text: &str) -> bool {
+        //Text which would be read back as something other than the same text, e.g. "12", "#N/A" or "=1+"
+        text.starts_with('\'') || Value::try_from_str(text).map_or(true, |value| value != Value::STRING(text.to_owned()))
+    }
+
+    fn array_text(values: &Array2<Value>, text: fn(&Value) -> String) -> String {
+        //Written as an array constant, columns separated by ',' and rows by ';'
+        let rows = values.rows().into_iter()
+            .map(|row| row.iter().map(text).collect::<Vec<String>>().join(","))
+            .collect::<Vec<String>>();
+        format!("{{{}}}", rows.join(";"))
+    }
+}
+
 pub fn solve_function(
     function: &FUNCTION,
-    values: &Vec<Value>) -> Result<Value, String> {
+    values: &[Value]) -> Result<Value, ErrorKind> {
     /*
     Implements the matching logic for determining which function to execute dependent on the given ENUM
      */
 
+    //Errors in any argument carry through to the result, apart from the functions which count or catch them
+    if !matches!(function, FUNCTION::COUNT | FUNCTION::COUNTA) && !is_logical(function) {
+        if let Some(kind) = first_error(values) {
+            return Err(kind)
+        }
+    }
 
-    //I feel like I should be borrowing values here instead of cloning but then have to deal with lifetimes in return component
-    return match function {
-        FUNCTION::ADD => add(values[0].clone(), values[1].clone()),
+    let ordering = |accept: fn(Ordering) -> bool| -> Result<Value, ErrorKind> {
+        let (val_1, val_2) = two_arguments(values)?;
+        compare(val_1, val_2).map(|res| Value::BOOLEAN(accept(res)))
+    };
 
-        _ => Ok(Value::I32(1))
+    //I feel like I should be borrowing values here instead of cloning but then have to deal with lifetimes in return component
+    match function {
+        FUNCTION::ADD => two_arguments(values).and_then(|(val_1, val_2)| add(val_1, val_2)),
+        FUNCTION::SUB => two_arguments(values).and_then(|(val_1, val_2)| sub(val_1, val_2)),
+        FUNCTION::MUL => two_arguments(values).and_then(|(val_1, val_2)| mul(val_1, val_2)),
+        FUNCTION::DIV => two_arguments(values).and_then(|(val_1, val_2)| div(val_1, val_2)),
+        FUNCTION::POW => two_arguments(values).and_then(|(val_1, val_2)| pow(val_1, val_2)),
+        FUNCTION::CONCAT => two_arguments(values).and_then(|(val_1, val_2)| concat(val_1, val_2)),
+        FUNCTION::MOD => two_arguments(values).and_then(|(val_1, val_2)| modulo(val_1, val_2)),
+        FUNCTION::NEG => one_argument(values).and_then(neg),
+        FUNCTION::ABS => one_argument(values).and_then(abs),
+        FUNCTION::SQRT => one_argument(values).and_then(sqrt),
+        FUNCTION::ROUND => optional_argument(values, Value::I32(0)).and_then(|(val_1, val_2)| round(val_1, val_2)),
+        FUNCTION::FLOOR => optional_argument(values, Value::I32(1)).and_then(|(val_1, val_2)| floor(val_1, val_2)),
+        FUNCTION::CEIL => optional_argument(values, Value::I32(1)).and_then(|(val_1, val_2)| ceil(val_1, val_2)),
+        FUNCTION::DECIMAL => optional_argument(values, Value::NULL()).and_then(|(val_1, val_2)| decimal::decimal(val_1, val_2)),
+        FUNCTION::EQ => ordering(|res| res == Ordering::Equal),
+        FUNCTION::NE => ordering(|res| res != Ordering::Equal),
+        FUNCTION::LT => ordering(|res| res == Ordering::Less),
+        FUNCTION::LTE => ordering(|res| res != Ordering::Greater),
+        FUNCTION::GT => ordering(|res| res == Ordering::Greater),
+        FUNCTION::GTE => ordering(|res| res != Ordering::Less),
+        FUNCTION::SUM => sum(values),
+        FUNCTION::AVERAGE => average(values),
+        FUNCTION::MIN => min(values),
+        FUNCTION::MAX => max(values),
+        FUNCTION::COUNT => count(values),
+        FUNCTION::COUNTA => counta(values),
+        FUNCTION::PRODUCT => product(values),
+        //The arguments are already solved, so every branch was solved whether it is taken or not
+        _ if is_logical(function) => logical(function, values, &|value| value.clone()),
+        _ => unreachable!("every function is solved above")
     }
 }
 
+fn first_error(values: &[Value]) -> Option<ErrorKind> {
+    values.iter().find_map(|value| match value {
+        Value::ERROR(kind) => Some(*kind),
+        Value::ARRAY(cells) => cells.iter().find_map(|cell| match cell {
+            Value::ERROR(kind) => Some(*kind),
+            _ => None
+        }),
+        _ => None
+    })
+}
+
+fn one_argument(values: &[Value]) -> Result<Value, ErrorKind> {
+    match values {
+        [val] => Ok(val.clone()),
+        _ => Err(ErrorKind::VALUE)
+    }
+}
 
+fn two_arguments(values: &[Value]) -> Result<(Value, Value), ErrorKind> {
+    match values {
+        [val_1, val_2] => Ok((val_1.clone(), val_2.clone())),
+        _ => Err(ErrorKind::VALUE)
+    }
+}
 
+fn optional_argument(values: &[Value], default: Value) -> Result<(Value, Value), ErrorKind> {
+    //For functions whose second argument can be left out
+    match values {
+        [val_1] => Ok((val_1.clone(), default)),
+        [val_1, val_2] => Ok((val_1.clone(), val_2.clone())),
+        _ => Err(ErrorKind::VALUE)
+    }
+}
 
 // Function to transform the input values into the same type, using the type with the most precision as the target datatype
-pub(crate) fn get_same_form(input_1: Value, input_2: Value) -> Result<(Value, Value), String>{
+pub(crate) fn get_same_form(input_1: Value, input_2: Value) -> Result<(Value, Value), ErrorKind>{
+    let input_1 = as_number(input_1)?;
+    let input_2 = as_number(input_2)?;
 
-    if matches!(input_1, Value::F32(_, _)) || matches!(input_2, Value::F32(_, _)){
-        Ok((
-            transform(input_1, Value::f32_default()).unwrap(),
-            transform(input_2, Value::f32_default()).unwrap()
-            ))
-    }else{
-        Ok((
-            transform(input_1, Value::i32_default()).unwrap(),
-            transform(input_2, Value::i32_default()).unwrap()
-        ))
+    /*
+    The numbers are promoted along I32 -> I64 -> F64 and I32 -> F32 -> F64 to the narrowest type holding both,
+    an F32 can not hold every I64 so the two meet at F64. A DECIMAL is kept exact whatever it is used with.
+     */
+    let either = |kind: fn(&Value) -> bool| kind(&input_1) || kind(&input_2);
+    let target = if either(|v| matches!(v, Value::DECIMAL(_, _))) {
+        Value::decimal_default()
+    } else if either(|v| matches!(v, Value::F64(_, _))) || (either(|v| matches!(v, Value::F32(_, _))) && either(|v| matches!(v, Value::I64(_)))) {
+        Value::f64_default()
+    } else if either(|v| matches!(v, Value::F32(_, _))) {
+        Value::f32_default()
+    } else if either(|v| matches!(v, Value::I64(_))) {
+        Value::i64_default()
+    } else {
+        Value::i32_default()
+    };
+
+    Ok((transform(input_1, target.clone())?, transform(input_2, target)?))
+}
+
+pub(crate) fn as_number(input: Value) -> Result<Value, ErrorKind>{
+    //Empty cells count as 0 and text holding a number is used as that number, the same as spreadsheets
+    match input {
+        Value::NULL() => Ok(Value::i32_default()),
+        Value::STRING(text) => Value::from_number_str(text.trim()).ok_or(ErrorKind::VALUE),
+        Value::ERROR(kind) => Err(kind),
+        _ => Ok(input)
     }
+}
 
+pub(crate) fn widen(val: f32) -> f64 {
+    //The f64 written the same as val, so 0.1 stays 0.1 rather than the nearest f64 to the f32 0.1
+    val.to_string().parse().unwrap_or(val as f64)
 }
 
-fn transform(input: Value, target: Value) -> Result<Value, String>{
+// Number of decimal places needed to show a computed float, trailing zeros are dropped up to a maximum of 6 places
+pub(crate) fn fitted_precision(val: f64) -> usize {
+    const MAX_PRECISION: usize = 6;
+    let rounded = format!("{val:.prec$}", val=val, prec=MAX_PRECISION);
+    MAX_PRECISION - rounded.chars().rev().take_while(|c| *c == '0').count()
+}
+
+fn transform(input: Value, target: Value) -> Result<Value, ErrorKind>{
     //!For the given input attempt to convert to the target value.
     //!Only required to list the applicable conversions for each input. Otherwise allow the Err.
     //!Used to convert data-types to values so functions can be applied to them.
 
-    //Const for Err to return
-    const NOT_VALID_CONVERSION:&str = "Not a valid conversion";
+    //Check if targets it DECIMAL and attempt to convert input, floats are taken as the digits they show
+    if matches!(target, Value::DECIMAL(_,_)){
+        return match input {
+            Value::I32(_) | Value::I64(_) | Value::F32(_, _) | Value::F64(_, _) | Value::DECIMAL(_, _) | Value::BOOLEAN(_) =>
+                to_decimal(&input).map(|(units, places)| Value::DECIMAL(units, places)),
+            Value::ERROR(kind) => Err(kind),
+            _ => Err(ErrorKind::VALUE)
+        }
+    }
+
+    //Check if targets it F64 and attempt to convert input
+    if matches!(target, Value::F64(_,_)){
+        return match input {
+            Value::I32(val) => Ok(Value::F64(val as f64, 0)),
+            Value::I64(val) => Ok(Value::F64(val as f64, 0)),
+            Value::F32(val, precision) => Ok(Value::F64(widen(val), precision)),
+            Value::F64(_, _) => Ok(input),
+            Value::BOOLEAN(val) => Ok(Value::F64(val as i32 as f64, 0)),
+            Value::ERROR(kind) => Err(kind),
+            _ => Err(ErrorKind::VALUE)
+        }
+    }
 
     //Check if targets it F32 and attempt to convert input
     if matches!(target, Value::F32(_,_)){
         return match input {
             Value::I32(val) => Ok(Value::F32(val as f32, 0)),
             Value::F32(_, _) => Ok(input),
-            _ => Err(NOT_VALID_CONVERSION.to_owned())
+            Value::BOOLEAN(val) => Ok(Value::F32(val as i32 as f32, 0)),
+            Value::ERROR(kind) => Err(kind),
+            _ => Err(ErrorKind::VALUE)
+        }
+    }
+
+    //Check if targets it I64 and attempt to convert input
+    if matches!(target, Value::I64(_)) {
+        return match input {
+            Value::I32(val) => Ok(Value::I64(val as i64)),
+            Value::I64(_) => Ok(input),
+            Value::BOOLEAN(val) => Ok(Value::I64(val as i64)),
+            Value::ERROR(kind) => Err(kind),
+            _ => Err(ErrorKind::VALUE)
         }
     }
 
@@ -207,19 +539,24 @@ fn transform(input: Value, target: Value) -> Result<Value, String>{
         return match input {
             Value::I32(_) => Ok(input),
             Value::F32(val, _) => Ok(Value::I32(val as i32)),
-            _ => Err(NOT_VALID_CONVERSION.to_owned())
+            //TRUE is 1 and FALSE is 0 in arithmetic
+            Value::BOOLEAN(val) => Ok(Value::I32(val as i32)),
+            Value::ERROR(kind) => Err(kind),
+            _ => Err(ErrorKind::VALUE)
         }
     }
 
-    Err("Not a valid target".to_string())
+    Err(ErrorKind::VALUE)
 }
 
 #[cfg(test)]
 mod tests {
     use chrono::{NaiveDateTime};
-    use crate::coordinate::Coordinate;
+    use crate::coordinate::{Anchor, Coordinate};
     use crate::FUNCTION;
-    use crate::value::{get_same_form, Value};
+    use ndarray::arr2;
+    use crate::error::ErrorKind;
+    use crate::value::{get_same_form, solve_function, Value};
 
     #[test]
     fn test_from_string_null(){
@@ -255,25 +592,89 @@ mod tests {
         assert_eq!(input, expected);
     }
 
+    #[test]
+    fn test_from_string_wide_numbers(){
+        //The narrowest type holding the number without loss is used
+        assert_eq!(Value::I32(i32::MAX), Value::create_from_str("2147483647".to_string()));
+        assert_eq!(Value::I64(3000000000), Value::create_from_str("3000000000".to_string()));
+        assert_eq!(Value::F32(2.5, 1), Value::create_from_str("2.5".to_string()));
+        assert_eq!(Value::F64(1234567.10, 2), Value::create_from_str("1234567.10".to_string()));
+        assert_eq!(Value::F64(12345678901234567890.0, 0), Value::create_from_str("12345678901234567890".to_string()));
+        assert_eq!("12345678901234567168", Value::create_from_str("12345678901234567890".to_string()).display());
+    }
+
+    #[test]
+    fn test_from_string_boolean(){
+        assert_eq!(Value::BOOLEAN(true), Value::create_from_str("TRUE".to_string()));
+        assert_eq!(Value::BOOLEAN(false), Value::create_from_str("false".to_string()));
+        assert_eq!(Value::STRING("TRUE".to_owned()), Value::create_from_str("'TRUE".to_string()));
+        assert_eq!(Value::STRING("truly".to_owned()), Value::create_from_str("truly".to_string()));
+    }
+
     #[test]
     fn test_f32_and_i32() {
         // assert the an f32 and a i32 input is transformed to 2 f32 values
         let (test_1_val_1, _test_1_val_2) = get_same_form(Value::F32(1.2, 2), Value::I32(4)).unwrap();
-        assert!(
-            match (test_1_val_1, _test_1_val_2) {
-                (Value::F32(_,_), Value::F32(_,_)) => true,
-                _ => false
-            }
-        );
+        assert!(matches!((test_1_val_1, _test_1_val_2), (Value::F32(_,_), Value::F32(_,_))));
 
         // assert the an i32 and a f32 input is transformed to 2 f32 values
         let (test_2_val_1, _test_2_val_2) = get_same_form(Value::I32(4), Value::F32(1.2, 2)).unwrap();
-        assert!(
-            match (test_2_val_1, _test_2_val_2) {
-                (Value::F32(_,_), Value::F32(_,_)) => true,
-                _ => false
-            }
-        );
+        assert!(matches!((test_2_val_1, _test_2_val_2), (Value::F32(_,_), Value::F32(_,_))));
+    }
+
+    #[test]
+    fn test_round_trip(){
+        //Every value written out as text reads back as the same value
+        let datetime = NaiveDateTime::parse_from_str("20230425 130000", "%Y%m%d %H%M%S").unwrap();
+        let values = [
+            Value::NULL(),
+            Value::I32(-3),
+            Value::F32(2.5, 2),
+            Value::F32(3.0, 0),
+            Value::I64(-3000000000),
+            Value::F64(1234567.10, 2),
+            Value::F64(12345678901.0, 0),
+            Value::DECIMAL(120050, 2),
+            Value::DECIMAL(-5, 0),
+            Value::STRING("text".to_owned()),
+            Value::STRING("12".to_owned()),
+            Value::STRING("".to_owned()),
+            Value::STRING("'quoted".to_owned()),
+            Value::STRING("#N/A".to_owned()),
+            Value::STRING("=1+".to_owned()),
+            Value::STRING("[A1]".to_owned()),
+            Value::STRING("true".to_owned()),
+            Value::BOOLEAN(true),
+            Value::BOOLEAN(false),
+            Value::DATETIME(datetime, "%Y%m%d %H%M%S".to_owned()),
+            Value::create_from_str("2021-04-18".to_string()),
+            Value::create_from_str("18/04/2021 21:03".to_string()),
+            Value::create_from_str("21:03:28.25".to_string()),
+            Value::create_from_str("2021-04-18T21:03:28+10:00".to_string()),
+            Value::create_from_str("Sun, 18 Apr 2021 21:03:28 +1000".to_string()),
+            Value::STRING("2021-04-18".to_owned()),
+            Value::ERROR(ErrorKind::DIV0),
+            Value::CELL_REFERENCE(Coordinate{row:2, column:27}, Anchor::RELATIVE),
+            Value::create_from_str("=[A:B]".to_string()),
+            Value::create_from_str("=-SUM([A1:B2], 1.50)*2^-[C3] & \"a\"\"b\" >= #REF!".to_string()),
+        ];
+        for value in values {
+            assert_eq!(value, Value::create_from_str(value.to_string()))
+        }
+    }
+
+    #[test]
+    fn test_display(){
+        assert_eq!("=[A1]+1", Value::create_from_str("[A1] + 1".to_string()).to_string());
+        assert_eq!("'12", Value::STRING("12".to_owned()).to_string());
+        assert_eq!("12", Value::STRING("12".to_owned()).display());
+        assert_eq!("3.", Value::F32(3.0, 0).to_string());
+        assert_eq!("3", Value::F32(3.0, 0).display());
+        assert_eq!("TRUE", Value::BOOLEAN(true).display());
+        assert_eq!("=DECIMAL(\"1200.50\")", Value::DECIMAL(120050, 2).to_string());
+        assert_eq!("1200.50", Value::DECIMAL(120050, 2).display());
+        assert_eq!("{1,a;2.5,}", Value::ARRAY(arr2(&[[Value::I32(1), Value::STRING("a".to_owned())],
+                                                     [Value::F32(2.5, 1), Value::NULL()]])).display());
     }
 
     #[test]
@@ -314,7 +715,7 @@ mod tests {
     #[test]
     fn test_from_string_cell_reference(){
         let input = Value::create_from_str("[ZA62]".to_owned());
-        let expected = Value::CELL_REFERENCE(Coordinate {row:62, column:677});
+        let expected = Value::CELL_REFERENCE(Coordinate {row:62, column:676}, Anchor::RELATIVE);
         assert_eq!(input, expected)
     }
 
@@ -327,4 +728,61 @@ mod tests {
 
         assert_eq!(input, expected)
     }
+
+    #[test]
+    fn test_from_string_nested_function(){
+        let input = Value::create_from_str("ADD(ADD(1,2),3)".to_owned());
+        let expected = Value::FUNCTION(FUNCTION::ADD,
+                                       vec![Value::FUNCTION(FUNCTION::ADD, vec![Value::I32(1), Value::I32(2)]),
+                                            Value::I32(3)]);
+
+        assert_eq!(input, expected)
+    }
+
+    #[test]
+    fn test_from_string_malformed_function(){
+        //Malformed formulas are kept as the text that was typed instead of panicking
+        for text in ["ADD(1,2", "ADD(", "ADD(1,)", "[A1", "=ADD(1,2))"] {
+            assert_eq!(Value::STRING(text.to_owned()), Value::create_from_str(text.to_owned()))
+        }
+    }
+
+    #[test]
+    fn test_from_string_error(){
+        assert_eq!(Value::ERROR(ErrorKind::DIV0), Value::create_from_str("#DIV/0!".to_owned()));
+        assert_eq!(Value::ERROR(ErrorKind::NAME), Value::create_from_str("=FOO(1)".to_owned()));
+        assert_eq!(Value::ERROR(ErrorKind::NAME), Value::create_from_str("=ADD(1, bar)".to_owned()));
+        //Without the '=' it was never a formula
+        assert_eq!(Value::STRING("FOO(1)".to_owned()), Value::create_from_str("FOO(1)".to_owned()));
+    }
+
+    #[test]
+    fn test_get_same_form_coercion(){
+        assert_eq!(Ok((Value::I32(0), Value::I32(2))), get_same_form(Value::NULL(), Value::I32(2)));
+        assert_eq!(Ok((Value::F32(2.5, 1), Value::F32(2.0, 0))), get_same_form(Value::STRING("2.5".to_owned()), Value::I32(2)));
+        assert_eq!(Err(ErrorKind::VALUE), get_same_form(Value::STRING("abc".to_owned()), Value::I32(2)));
+        assert_eq!(Err(ErrorKind::REF), get_same_form(Value::I32(1), Value::ERROR(ErrorKind::REF)));
+        assert_eq!(Ok((Value::I32(1), Value::I32(2))), get_same_form(Value::BOOLEAN(true), Value::I32(2)));
+        assert_eq!(Ok((Value::F32(0.0, 0), Value::F32(2.5, 1))), get_same_form(Value::BOOLEAN(false), Value::F32(2.5, 1)));
+    }
+
+    #[test]
+    fn test_get_same_form_promotion(){
+        assert_eq!(Ok((Value::I64(1), Value::I64(2))), get_same_form(Value::I32(1), Value::I64(2)));
+        assert_eq!(Ok((Value::F64(1.0, 0), Value::F64(2.5, 1))), get_same_form(Value::I32(1), Value::F64(2.5, 1)));
+        assert_eq!(Ok((Value::F64(0.1, 1), Value::F64(2.5, 1))), get_same_form(Value::F32(0.1, 1), Value::F64(2.5, 1)));
+        //An F32 can not hold every I64 so the two meet at F64
+        assert_eq!(Ok((Value::F64(3000000000.0, 0), Value::F64(0.5, 1))), get_same_form(Value::I64(3000000000), Value::F32(0.5, 1)));
+    }
+
+    #[test]
+    fn test_solve_function_propagates_errors(){
+        let error = Value::ERROR(ErrorKind::DIV0);
+        assert_eq!(Err(ErrorKind::DIV0), solve_function(&FUNCTION::CONCAT, &[Value::I32(1), error.clone()]));
+        assert_eq!(Err(ErrorKind::DIV0), solve_function(&FUNCTION::SUM, &[Value::ARRAY(arr2(&[[Value::I32(1), error.clone()]]))]));
+        assert_eq!(Ok(Value::I32(1)), solve_function(&FUNCTION::COUNT, &[Value::ARRAY(arr2(&[[Value::I32(1), error.clone()]]))]));
+        assert_eq!(Ok(Value::I32(2)), solve_function(&FUNCTION::COUNTA, &[Value::I32(1), error]));
+        assert_eq!(Err(ErrorKind::VALUE), solve_function(&FUNCTION::ADD, &[Value::I32(1)]));
+    }
 }
+
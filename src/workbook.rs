@@ -0,0 +1,471 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use ndarray::Axis;
+use crate::coordinate::Coordinate;
+use crate::dependencies::{recalculate, Graph};
+use crate::error::ErrorKind;
+use crate::iteration::{iterate, Convergence};
+use crate::shift::Shift;
+use crate::value::Value;
+use crate::DataFrame;
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum WorkbookError{
+    InvalidSheetName(String),
+    //Sheet names are case insensitive, so "Data" and "data" are the same sheet
+    DuplicateSheetName(String),
+    UnknownSheet(String),
+    OutOfBounds(String, Coordinate),
+    //Rows or columns that can not be inserted or deleted on the sheet
+    InvalidShift(String, String)
+}
+
+impl fmt::Display for WorkbookError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorkbookError::InvalidSheetName(name) => write!(f, "'{}' can not be used as a sheet name", name),
+            WorkbookError::DuplicateSheetName(name) => write!(f, "there is already a sheet called '{}'", name),
+            WorkbookError::UnknownSheet(name) => write!(f, "there is no sheet called '{}'", name),
+            WorkbookError::OutOfBounds(name, coordinate) => write!(f, "cell {} is outside of sheet '{}'", coordinate, name),
+            WorkbookError::InvalidShift(name, message) => write!(f, "{} on sheet '{}'", message, name)
+        }
+    }
+}
+
+//A cell on one sheet of a workbook, sheets are numbered in the order they were added
+#[derive(PartialEq)]
+#[derive(Eq)]
+#[derive(PartialOrd)]
+#[derive(Ord)]
+#[derive(Hash)]
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub(crate) struct SheetCell{
+    sheet: usize,
+    coordinate: Coordinate
+}
+
+//Named DataFrames whose formulas can reference the cells of each other, e.g. [Sheet2!B3] or [Sheet2!A0:B3]
+#[derive(Debug)]
+#[derive(Default)]
+pub struct Workbook{
+    sheets: Vec<(String, DataFrame)>,
+    //For each cell, the cells whose formulas reference it from a sheet reference
+    references: HashMap<SheetCell, Vec<SheetCell>>,
+    //For each formula cell, the cells its sheet references point at
+    precedents: HashMap<SheetCell, Vec<SheetCell>>,
+    //Circular references passing through more than one sheet, loops within one sheet are kept by that sheet
+    cycles: Vec<Vec<SheetCell>>
+}
+
+impl Workbook {
+    pub fn new() -> Self {
+        Workbook::default()
+    }
+
+    pub fn from_sheets(sheets: Vec<(String, DataFrame)>) -> Result<Self, WorkbookError> {
+        /*
+        Creates a workbook from named DataFrames in order, such as the sheets read from an xlsx file
+         */
+        let mut workbook = Workbook::new();
+        for (name, df) in sheets {
+            workbook.check_name(&name, None)?;
+            workbook.sheets.push((name, df));
+        }
+        workbook.relink(vec![]);
+        Ok(workbook)
+    }
+
+    pub fn add_sheet(&mut self, name: &str, df: DataFrame) -> Result<(), WorkbookError> {
+        /*
+        Adds a sheet after the existing ones. Formulas already referencing the name start reading from it.
+         */
+        self.check_name(name, None)?;
+        self.sheets.push((name.to_owned(), df));
+        self.relink(vec![]);
+        Ok(())
+    }
+
+    pub fn rename_sheet(&mut self, old: &str, new: &str) -> Result<(), WorkbookError> {
+        /*
+        Renames a sheet and rewrites every formula referencing it to use the new name
+         */
+        let index = self.index(old)?;
+        self.check_name(new, Some(index))?;
+
+        let old = self.sheets[index].0.clone();
+        for (_, df) in self.sheets.iter_mut() {
+            for cell in df.data.iter_mut() {
+                cell.rename_sheet(&old, new);
+            }
+        }
+        self.sheets[index].0 = new.to_owned();
+        self.relink(vec![]);
+        Ok(())
+    }
+
+    pub fn sheet(&self, name: &str) -> Option<&DataFrame> {
+        find_sheet(&self.sheets, name).map(|index| &self.sheets[index].1)
+    }
+
+    pub fn sheets(&self) -> Vec<(&str, &DataFrame)> {
+        //Every sheet in order with its name, as taken by write_xlsx
+        self.sheets.iter().map(|(name, df)| (name.as_str(), df)).collect()
+    }
+
+    pub fn value(&self, sheet: &str, coordinate: Coordinate) -> Option<&Value> {
+        //The solved value of a cell
+        self.sheet(sheet)?.value(coordinate)
+    }
+
+    pub fn set_cell(&mut self, sheet: &str, coordinate: Coordinate, value: Value) -> Result<Vec<(String, Coordinate)>, WorkbookError> {
+        /*
+        Replaces the value in a cell and recalculates it along with every cell depending on it on any sheet.
+        Returns the recalculated cells in the order they were solved.
+         */
+        let index = self.index(sheet)?;
+        let (name, df) = &mut self.sheets[index];
+        let cell = df.data.get_mut((coordinate.row, coordinate.column))
+            .ok_or_else(|| WorkbookError::OutOfBounds(name.to_owned(), coordinate))?;
+        *cell = value;
+        df.unlink(coordinate);
+        df.link(coordinate);
+
+        let cell = SheetCell{sheet: index, coordinate};
+        self.unlink(cell);
+        self.link(cell);
+        Ok(recalculate(self, &[cell]).into_iter().map(|cell| (self.sheets[cell.sheet].0.clone(), cell.coordinate)).collect())
+    }
+
+    pub fn set_cell_from_str(&mut self, sheet: &str, coordinate: Coordinate, input: &str) -> Result<Vec<(String, Coordinate)>, String> {
+        /*
+        Parses user input into a cell, an input starting with '=' that is not a valid formula is rejected with the
+        position of the syntax error
+         */
+        let value = match self.sheet(sheet) {
+            Some(df) => df.parse_input(coordinate, input)?,
+            None => Value::try_from_str(input).map_err(|error| format!("Invalid formula, {}", error))?
+        };
+        self.set_cell(sheet, coordinate, value).map_err(|error| error.to_string())
+    }
+
+    pub fn insert_rows(&mut self, sheet: &str, at: usize, count: usize) -> Result<(), WorkbookError> {
+        //Inserts empty rows before row at, references from every sheet follow the cells they pointed at
+        self.shift(sheet, Shift::insert(Axis(0), at, count))
+    }
+
+    pub fn delete_rows(&mut self, sheet: &str, at: usize, count: usize) -> Result<(), WorkbookError> {
+        self.shift(sheet, Shift::delete(Axis(0), at, count))
+    }
+
+    pub fn insert_columns(&mut self, sheet: &str, at: usize, count: usize) -> Result<(), WorkbookError> {
+        self.shift(sheet, Shift::insert(Axis(1), at, count))
+    }
+
+    pub fn delete_columns(&mut self, sheet: &str, at: usize, count: usize) -> Result<(), WorkbookError> {
+        self.shift(sheet, Shift::delete(Axis(1), at, count))
+    }
+
+    pub fn cycles(&self) -> Vec<Vec<(&str, Coordinate)>> {
+        /*
+        The circular references passing through more than one sheet, each given as the cells forming the loop in the
+        order they reference each other. Loops within one sheet are found in the cycles of that sheet.
+         */
+        self.cycles.iter()
+            .map(|cycle| cycle.iter().map(|cell| (self.sheets[cell.sheet].0.as_str(), cell.coordinate)).collect())
+            .collect()
+    }
+
+    fn index(&self, name: &str) -> Result<usize, WorkbookError> {
+        find_sheet(&self.sheets, name).ok_or_else(|| WorkbookError::UnknownSheet(name.to_owned()))
+    }
+
+    fn check_name(&self, name: &str, renaming: Option<usize>) -> Result<(), WorkbookError> {
+        //A sheet being renamed can keep its own name, e.g. to change its case
+        if !valid_sheet_name(name) {
+            return Err(WorkbookError::InvalidSheetName(name.to_owned()))
+        }
+        match find_sheet(&self.sheets, name) {
+            Some(index) if Some(index) != renaming => Err(WorkbookError::DuplicateSheetName(name.to_owned())),
+            _ => Ok(())
+        }
+    }
+
+    fn shift(&mut self, sheet: &str, shift: Shift) -> Result<(), WorkbookError> {
+        /*
+        Moves the cells of a sheet and rewrites the references to them on every sheet, then solves the sheet again
+        along with every cell referencing another sheet
+         */
+        let index = self.index(sheet)?;
+        let name = self.sheets[index].0.clone();
+        shift.check(self.sheets[index].1.dim()).map_err(|message| WorkbookError::InvalidShift(name.clone(), message))?;
+
+        //Formulas on other sheets which lost their last reference to another sheet still have to be solved again
+        let mut rewritten = vec![];
+        for (other, (_, df)) in self.sheets.iter_mut().enumerate() {
+            if other == index {
+                df.move_cells(shift, Some(&name));
+                df.rebuild();
+                continue
+            }
+            for ((row, column), value) in df.data.indexed_iter_mut() {
+                let moved = shift.rewrite(value, false, Some(&name));
+                if moved != *value {
+                    *value = moved;
+                    rewritten.push(SheetCell{sheet: other, coordinate: Coordinate{row, column}});
+                }
+            }
+        }
+        self.relink(rewritten);
+        Ok(())
+    }
+
+    fn link(&mut self, cell: SheetCell) {
+        //Records the cells on other sheets the given cell references and adds it as a dependent of each of them
+        let data = &self.sheets[cell.sheet].1.data;
+        let mut precedents: Vec<SheetCell> = data[(cell.coordinate.row, cell.coordinate.column)].sheet_references().into_iter()
+            .filter_map(|(name, reference)| find_sheet(&self.sheets, name).map(|sheet| (sheet, reference)))
+            .flat_map(|(sheet, reference)| reference.precedents(self.sheets[sheet].1.dim()).into_iter()
+                .map(move |coordinate| SheetCell{sheet, coordinate}))
+            .collect();
+        precedents.sort();
+        precedents.dedup();
+
+        for precedent in &precedents {
+            self.references.entry(*precedent).or_default().push(cell);
+        }
+        if !precedents.is_empty() {
+            self.precedents.insert(cell, precedents);
+        }
+    }
+
+    fn unlink(&mut self, cell: SheetCell) {
+        //Removes the given cell as a dependent of every cell it referenced on other sheets
+        for precedent in self.precedents.remove(&cell).unwrap_or_default() {
+            if let Some(dependents) = self.references.get_mut(&precedent) {
+                dependents.retain(|dependent| *dependent != cell);
+                if dependents.is_empty() {
+                    self.references.remove(&precedent);
+                }
+            }
+        }
+    }
+
+    fn relink(&mut self, mut changed: Vec<SheetCell>) {
+        /*
+        Rebuilds the links between sheets after sheets are added, renamed or moved, then solves every cell referencing
+        another sheet again along with the changed cells, and everything depending on them
+         */
+        self.references.clear();
+        self.precedents.clear();
+        self.cycles.clear();
+        let cells: Vec<SheetCell> = self.sheets.iter().enumerate()
+            .flat_map(|(sheet, (_, df))| df.data.indexed_iter()
+                .filter(|(_, value)| !value.sheet_references().is_empty())
+                .map(move |((row, column), _)| SheetCell{sheet, coordinate: Coordinate{row, column}}))
+            .collect();
+        for cell in &cells {
+            self.link(*cell);
+        }
+        changed.extend(cells);
+        recalculate(self, &changed);
+    }
+}
+
+impl Graph for Workbook {
+    type Cell = SheetCell;
+
+    fn dependents(&self, cell: &SheetCell) -> Vec<SheetCell> {
+        let within = self.sheets[cell.sheet].1.dependents(&cell.coordinate).into_iter()
+            .map(|coordinate| SheetCell{sheet: cell.sheet, coordinate});
+        within.chain(self.references.get(cell).into_iter().flatten().cloned()).collect()
+    }
+
+    fn precedents(&self, cell: &SheetCell) -> Vec<SheetCell> {
+        let within = Graph::precedents(&self.sheets[cell.sheet].1, &cell.coordinate).into_iter()
+            .map(|coordinate| SheetCell{sheet: cell.sheet, coordinate});
+        within.chain(self.precedents.get(cell).into_iter().flatten().cloned()).collect()
+    }
+
+    fn solve(&self, cell: &SheetCell) -> Value {
+        let df = &self.sheets[cell.sheet].1;
+        df.solve_in(&df.data[(cell.coordinate.row, cell.coordinate.column)], &self.sheets)
+    }
+
+    fn solved_mut(&mut self, cell: &SheetCell) -> &mut Value {
+        &mut self.sheets[cell.sheet].1.solved[(cell.coordinate.row, cell.coordinate.column)]
+    }
+
+    fn forget_loops(&mut self, cells: &HashSet<SheetCell>) {
+        for (sheet, (_, df)) in self.sheets.iter_mut().enumerate() {
+            let within: HashSet<Coordinate> = cells.iter().filter(|cell| cell.sheet == sheet).map(|cell| cell.coordinate).collect();
+            df.forget_loops(&within);
+        }
+        self.cycles.retain(|cycle| !cycle.iter().any(|cell| cells.contains(cell)));
+    }
+
+    fn solve_loop(&mut self, component: &[SheetCell], path: Vec<SheetCell>) {
+        /*
+        A loop within one sheet is solved with the iterative calculation setting of that sheet, a loop through more
+        than one sheet is always a #CYCLE! error
+         */
+        let sheet = component[0].sheet;
+        let within = component.iter().all(|cell| cell.sheet == sheet);
+        match self.sheets[sheet].1.iterative {
+            Some(settings) if within => {
+                let (iterations, converged) = iterate(self, component, settings);
+                let cells = component.iter().map(|cell| cell.coordinate).collect();
+                self.sheets[sheet].1.convergence.push(Convergence{cells, iterations, converged});
+            },
+            _ => for cell in component {
+                *self.solved_mut(cell) = Value::ERROR(ErrorKind::CYCLE);
+            }
+        }
+
+        if within {
+            self.sheets[sheet].1.cycles.push(path.iter().map(|cell| cell.coordinate).collect());
+        } else {
+            self.cycles.push(path);
+        }
+    }
+}
+
+pub(crate) fn find_sheet(sheets: &[(String, DataFrame)], name: &str) -> Option<usize> {
+    sheets.iter().position(|(sheet, _)| same_sheet(sheet, name))
+}
+
+pub(crate) fn same_sheet(name_1: &str, name_2: &str) -> bool {
+    //Sheet names are case insensitive
+    name_1.to_lowercase() == name_2.to_lowercase()
+}
+
+pub(crate) fn valid_sheet_name(name: &str) -> bool {
+    //The same rules spreadsheets use, so every workbook can be saved as an xlsx file
+    !name.is_empty() && name.chars().count() <= 31 && !name.contains([':', '\\', '/', '?', '*', '[', ']'])
+        && !name.starts_with('\'') && !name.ends_with('\'')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+    use crate::iteration::IterativeCalculation;
+    use crate::test_helpers::{coordinate, sheet};
+    use crate::value::Value;
+    use crate::workbook::{Workbook, WorkbookError};
+
+    #[test]
+    fn test_sheet_references(){
+        let mut input = Workbook::new();
+        input.add_sheet("Totals", sheet(&[&["=SUM([Sales!A0:A2])", "=[Sales!B0]*2", "=['Unit Costs'!A0]"]])).unwrap();
+        assert_eq!(Some(&Value::ERROR(ErrorKind::REF)), input.value("Totals", coordinate(0, 0)));
+        assert_eq!(Some(&Value::ERROR(ErrorKind::REF)), input.value("Totals", coordinate(0, 1)));
+
+        //Formulas start reading from a sheet as soon as it is added
+        input.add_sheet("Sales", sheet(&[&["1", "5"], &["2", ""], &["3", ""]])).unwrap();
+        input.add_sheet("Unit Costs", sheet(&[&["2.5"]])).unwrap();
+        assert_eq!(Some(&Value::I32(6)), input.value("totals", coordinate(0, 0)));
+        assert_eq!(Some(&Value::I32(10)), input.value("Totals", coordinate(0, 1)));
+        assert_eq!(Some(&Value::F32(2.5, 1)), input.value("Totals", coordinate(0, 2)));
+
+        //Edits are recalculated across sheets in dependency order
+        let order = input.set_cell("Sales", coordinate(1, 0), Value::I32(20)).unwrap();
+        assert_eq!(vec![("Sales".to_owned(), coordinate(1, 0)), ("Totals".to_owned(), coordinate(0, 0))], order);
+        assert_eq!(Some(&Value::I32(24)), input.value("Totals", coordinate(0, 0)));
+
+        //Including formulas on the same sheet depending on a sheet reference
+        input.set_cell_from_str("Sales", coordinate(2, 1), "=[Totals!B0]+[A0]").unwrap();
+        input.set_cell("Sales", coordinate(0, 1), Value::I32(7)).unwrap();
+        assert_eq!(Some(&Value::I32(15)), input.value("Sales", coordinate(2, 1)));
+    }
+
+    #[test]
+    fn test_rename_sheet(){
+        let mut input = Workbook::from_sheets(vec![
+            ("Data".to_owned(), sheet(&[&["4"]])),
+            ("Report".to_owned(), sheet(&[&["=[Data!A0]+[data!A0]", "=[Other!A0]"]]))
+        ]).unwrap();
+
+        input.rename_sheet("Data", "Raw Data").unwrap();
+        assert_eq!(Some("=['Raw Data'!A0]+['Raw Data'!A0]".to_owned()), input.sheet("Report").unwrap().raw(coordinate(0, 0)).map(Value::to_string));
+        assert_eq!(Some(&Value::I32(8)), input.value("Report", coordinate(0, 0)));
+        input.set_cell("Raw Data", coordinate(0, 0), Value::I32(1)).unwrap();
+        assert_eq!(Some(&Value::I32(2)), input.value("Report", coordinate(0, 0)));
+
+        //Renaming onto a name a formula was waiting for links it up
+        input.rename_sheet("Raw Data", "Other").unwrap();
+        assert_eq!(Some(&Value::I32(1)), input.value("Report", coordinate(0, 1)));
+
+        assert_eq!(Err(WorkbookError::DuplicateSheetName("REPORT".to_owned())), input.rename_sheet("Other", "REPORT"));
+        assert_eq!(Err(WorkbookError::InvalidSheetName("a:b".to_owned())), input.rename_sheet("Other", "a:b"));
+        assert_eq!(Err(WorkbookError::UnknownSheet("Data".to_owned())), input.rename_sheet("Data", "New"));
+        assert_eq!(Ok(()), input.rename_sheet("Other", "OTHER"));
+    }
+
+    #[test]
+    fn test_cycles_across_sheets(){
+        let mut input = Workbook::new();
+        input.add_sheet("One", sheet(&[&["=[Two!A0]+1", "=[A0]"]])).unwrap();
+        input.add_sheet("Two", sheet(&[&["=[One!A0]", "=[A0]"]])).unwrap();
+
+        assert_eq!(vec![vec![("One", coordinate(0, 0)), ("Two", coordinate(0, 0))]], input.cycles());
+        for (name, cell) in [("One", coordinate(0, 0)), ("One", coordinate(0, 1)), ("Two", coordinate(0, 1))] {
+            assert_eq!(Some(&Value::ERROR(ErrorKind::CYCLE)), input.value(name, cell));
+        }
+
+        input.set_cell("Two", coordinate(0, 0), Value::I32(3)).unwrap();
+        assert!(input.cycles().is_empty());
+        assert_eq!(Some(&Value::I32(4)), input.value("One", coordinate(0, 1)));
+
+        //A loop within one sheet through a sheet reference to itself is iterated with the setting of the sheet
+        let mut looped = sheet(&[&["=[Three!B0]/2+1", "=[A0]"]]);
+        looped.set_iterative_calculation(Some(IterativeCalculation::default()));
+        input.add_sheet("Three", looped).unwrap();
+        assert!(input.sheet("Three").unwrap().converged());
+        assert_eq!(1, input.sheet("Three").unwrap().cycles().len());
+        match input.value("Three", coordinate(0, 0)) {
+            Some(Value::F32(val, _)) => assert!((val - 2.0).abs() < 0.01),
+            other => panic!("Expected a number, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn test_shift(){
+        let mut input = Workbook::new();
+        input.add_sheet("Data", sheet(&[&["1", "=[A1]"], &["2", ""], &["3", ""]])).unwrap();
+        input.add_sheet("Report", sheet(&[&["=SUM([Data!A0:A2])", "=[data!A2]", "=[Data!A1]", "=[A0]"]])).unwrap();
+
+        input.insert_rows("Data", 1, 1).unwrap();
+        let report = |input: &Workbook, column| input.sheet("Report").unwrap().raw(coordinate(0, column)).unwrap().to_string();
+        assert_eq!("=SUM([Data!A0:A3])", report(&input, 0));
+        assert_eq!("=[data!A3]", report(&input, 1));
+        assert_eq!("=[A2]", input.sheet("Data").unwrap().raw(coordinate(0, 1)).unwrap().to_string());
+        input.set_cell("Data", coordinate(1, 0), Value::I32(10)).unwrap();
+        assert_eq!(Some(&Value::I32(16)), input.value("Report", coordinate(0, 0)));
+
+        //Only references to the sheet being changed move, Report's own [A0] stays put
+        input.delete_rows("Data", 2, 1).unwrap();
+        assert_eq!("#REF!", report(&input, 2));
+        assert_eq!("=[A0]", report(&input, 3));
+        assert_eq!(Some(&Value::ERROR(ErrorKind::REF)), input.value("Report", coordinate(0, 2)));
+        assert_eq!(Some(&Value::I32(14)), input.value("Report", coordinate(0, 3)));
+
+        assert_eq!(
+            Err(WorkbookError::InvalidShift("Data".to_owned(), "Can not delete columns 1 to 2, there are only 2 columns".to_owned())),
+            input.delete_columns("data", 1, 2)
+        );
+    }
+
+    #[test]
+    fn test_errors(){
+        let mut input = Workbook::new();
+        input.add_sheet("Data", sheet(&[&["1"]])).unwrap();
+        assert_eq!(Err(WorkbookError::DuplicateSheetName("data".to_owned())), input.add_sheet("data", sheet(&[])));
+        assert_eq!(Err(WorkbookError::InvalidSheetName("".to_owned())), input.add_sheet("", sheet(&[])));
+        assert_eq!(Err(WorkbookError::OutOfBounds("Data".to_owned(), coordinate(1, 0))), input.set_cell("Data", coordinate(1, 0), Value::I32(1)));
+        assert_eq!(Err(WorkbookError::UnknownSheet("None".to_owned())), input.set_cell("None", coordinate(0, 0), Value::I32(1)));
+        assert!(input.set_cell_from_str("Data", coordinate(0, 0), "=[Data!A0:]").is_err());
+    }
+}